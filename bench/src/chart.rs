@@ -0,0 +1,358 @@
+use crate::latency::Latency;
+use crate::machine::Cache;
+use crate::report::Measurement;
+use crate::size;
+use std::io::{self, Write};
+
+const WIDTH: f64 = 960.0;
+/// The smallest height of a panel. Panels with long legends are taller.
+const HEIGHT: f64 = 360.0;
+const LEFT: f64 = 70.0;
+/// The room for the legend.
+const RIGHT: f64 = 200.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 50.0;
+const LEGEND_LINE: f64 = 16.0;
+
+const COLOURS: &[&str] = &[
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+    "#bcbd22", "#17becf",
+];
+
+/// The bandwidth of a kernel in one configuration, by size in increasing order.
+struct Series {
+    label: String,
+    points: Vec<(usize, f64)>,
+}
+
+/// The kernels sharing the part of their names before the first underscore, such as `mov` or
+/// `simd`, in one panel.
+struct Family<'a> {
+    name: &'a str,
+    title: String,
+    /// What the vertical axis measures.
+    unit: &'static str,
+    series: Vec<Series>,
+}
+
+/// Writes a self-contained SVG document charting the bandwidth against the size of every kernel, a
+/// panel per family, then the latency if measured, on a logarithmic size axis shared by all the
+/// panels. Dashed lines mark the sizes of the data caches.
+pub fn write(
+    measurements: &[Measurement],
+    latencies: &[Latency],
+    caches: &[Cache],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let mut families = families(measurements);
+    if !latencies.is_empty() {
+        families.push(latency(latencies));
+    }
+    let sizes = measurements
+        .iter()
+        .map(|measurement| measurement.size)
+        .chain(latencies.iter().map(|latency| latency.size));
+    let (mut low, mut high) = match (sizes.clone().min(), sizes.max()) {
+        (Some(min), Some(max)) => ((min as f64).log2(), (max as f64).log2()),
+        _ => (0.0, 1.0),
+    };
+    if high - low < 1.0 {
+        low -= 0.5;
+        high += 0.5;
+    }
+    let axis = Axis { low, high };
+
+    let heights: Vec<f64> = families
+        .iter()
+        .map(|family| HEIGHT.max(TOP + LEGEND_LINE * family.series.len() as f64 + BOTTOM))
+        .collect();
+    let total: f64 = heights.iter().sum();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="12">"#,
+        WIDTH, total
+    )?;
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    let caches: Vec<&Cache> = caches
+        .iter()
+        .filter(|cache| !cache.name.ends_with('i'))
+        .collect();
+    let mut offset = 0.0;
+    for (family, &height) in families.iter().zip(&heights) {
+        writeln!(out, r#"<g transform="translate(0,{})">"#, offset)?;
+        panel(family, &axis, height, &caches, out)?;
+        writeln!(out, "</g>")?;
+        offset += height;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Groups the measurements into families and series, in the order they first appear. The labels
+/// of the series name the cache, threads and backing store only if they vary.
+fn families(measurements: &[Measurement]) -> Vec<Family<'_>> {
+    let mut configurations = Vec::new();
+    for measurement in measurements {
+        let configuration = (
+            measurement.cache.as_str(),
+            measurement.threads,
+            measurement.backing.as_str(),
+        );
+        if !configurations.contains(&configuration) {
+            configurations.push(configuration);
+        }
+    }
+    let mixed = configurations.len() > 1;
+
+    let mut families: Vec<Family> = Vec::new();
+    let mut keys: Vec<Vec<(&str, &str, usize, &str)>> = Vec::new();
+    for measurement in measurements {
+        let name = measurement.kernel.split('_').next().unwrap_or("");
+        let index = match families.iter().position(|family| family.name == name) {
+            Some(index) => index,
+            None => {
+                families.push(Family {
+                    name,
+                    title: format!("{} kernels", name),
+                    unit: "GB/s",
+                    series: Vec::new(),
+                });
+                keys.push(Vec::new());
+                families.len() - 1
+            }
+        };
+        let key = (
+            measurement.kernel.as_str(),
+            measurement.cache.as_str(),
+            measurement.threads,
+            measurement.backing.as_str(),
+        );
+        let series = match keys[index].iter().position(|&k| k == key) {
+            Some(series) => series,
+            None => {
+                let label = if mixed {
+                    format!(
+                        "{} ({}, {} thread{}, {})",
+                        measurement.kernel,
+                        measurement.cache,
+                        measurement.threads,
+                        if measurement.threads == 1 { "" } else { "s" },
+                        measurement.backing
+                    )
+                } else {
+                    measurement.kernel.clone()
+                };
+                keys[index].push(key);
+                families[index].series.push(Series {
+                    label,
+                    points: Vec::new(),
+                });
+                keys[index].len() - 1
+            }
+        };
+        families[index].series[series]
+            .points
+            .push((measurement.size, measurement.bandwidth));
+    }
+    for family in &mut families {
+        for series in &mut family.series {
+            series.points.sort_by_key(|&(size, _)| size);
+        }
+    }
+    families
+}
+
+/// The nanoseconds per load, a series per backing store.
+fn latency(latencies: &[Latency]) -> Family<'_> {
+    let mut series: Vec<Series> = Vec::new();
+    for latency in latencies {
+        let point = (latency.size, latency.nanoseconds);
+        match series
+            .iter_mut()
+            .find(|series| series.label == latency.backing)
+        {
+            Some(series) => series.points.push(point),
+            None => series.push(Series {
+                label: latency.backing.clone(),
+                points: vec![point],
+            }),
+        }
+    }
+    for series in &mut series {
+        series.points.sort_by_key(|&(size, _)| size);
+    }
+    Family {
+        name: "latency",
+        title: "latency of a pointer chase".to_string(),
+        unit: "ns per load",
+        series,
+    }
+}
+
+/// The size axis, from `2^low` to `2^high` bytes.
+struct Axis {
+    low: f64,
+    high: f64,
+}
+
+impl Axis {
+    fn x(&self, size: usize) -> f64 {
+        let fraction = ((size as f64).log2() - self.low) / (self.high - self.low);
+        LEFT + fraction * (WIDTH - LEFT - RIGHT)
+    }
+
+    fn contains(&self, size: usize) -> bool {
+        let log = (size as f64).log2();
+        self.low <= log && log <= self.high
+    }
+}
+
+fn panel(
+    family: &Family,
+    axis: &Axis,
+    height: f64,
+    caches: &[&Cache],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let (right, bottom) = (WIDTH - RIGHT, height - BOTTOM);
+    let highest = family
+        .series
+        .iter()
+        .flat_map(|series| series.points.iter().map(|&(_, bandwidth)| bandwidth))
+        .fold(0.0, f64::max);
+    let step = step(highest / 6.0);
+    let top = (highest / step).ceil().max(1.0) * step;
+    let y = |bandwidth: f64| bottom - bandwidth / top * (bottom - TOP);
+
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="14" font-weight="bold">{}</text>"#,
+        LEFT,
+        TOP - 16.0,
+        escape(&family.title)
+    )?;
+
+    // The grid and the bandwidth axis, with as many decimals as the step needs.
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    for i in 0..=(top / step).round() as usize {
+        let bandwidth = i as f64 * step;
+        writeln!(
+            out,
+            r##"<line x1="{0}" y1="{1:.1}" x2="{2}" y2="{1:.1}" stroke="#ddd"/><text x="{3}" y="{4:.1}" text-anchor="end">{5:.6$}</text>"##,
+            LEFT,
+            y(bandwidth),
+            right,
+            LEFT - 6.0,
+            y(bandwidth) + 4.0,
+            bandwidth,
+            decimals
+        )?;
+    }
+    writeln!(
+        out,
+        r#"<text transform="translate({},{}) rotate(-90)" text-anchor="middle">{}</text>"#,
+        LEFT - 50.0,
+        (TOP + bottom) / 2.0,
+        family.unit
+    )?;
+
+    // The size axis, labelled at powers of two, at most a dozen of them.
+    let (first, last) = (axis.low.ceil() as u32, axis.high.floor() as u32);
+    let stride = last.saturating_sub(first) / 12 + 1;
+    for power in (first..=last).filter(|power| power % stride == 0) {
+        let x = axis.x(1 << power);
+        writeln!(
+            out,
+            r##"<line x1="{0:.1}" y1="{1}" x2="{0:.1}" y2="{2}" stroke="#000"/><text x="{0:.1}" y="{3}" text-anchor="middle">{4}</text>"##,
+            x,
+            bottom,
+            bottom + 5.0,
+            bottom + 18.0,
+            size::format(1 << power)
+        )?;
+    }
+    writeln!(
+        out,
+        r##"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="#000"/><line x1="{0}" y1="{3}" x2="{0}" y2="{1}" stroke="#000"/>"##,
+        LEFT, bottom, right, TOP
+    )?;
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}" text-anchor="middle">size</text>"#,
+        (LEFT + right) / 2.0,
+        bottom + 38.0
+    )?;
+
+    for cache in caches.iter().filter(|cache| axis.contains(cache.size)) {
+        let x = axis.x(cache.size);
+        writeln!(
+            out,
+            r##"<line x1="{0:.1}" y1="{1}" x2="{0:.1}" y2="{2}" stroke="#888" stroke-dasharray="4 4"/><text x="{3:.1}" y="{4}" fill="#555">{5} {6}</text>"##,
+            x,
+            TOP,
+            bottom,
+            x + 3.0,
+            TOP + 12.0,
+            escape(&cache.name),
+            size::format(cache.size)
+        )?;
+    }
+
+    for (i, series) in family.series.iter().enumerate() {
+        let colour = COLOURS[i % COLOURS.len()];
+        let points: Vec<(f64, f64)> = series
+            .points
+            .iter()
+            .map(|&(size, bandwidth)| (axis.x(size), y(bandwidth)))
+            .collect();
+        let line: Vec<String> = points
+            .iter()
+            .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+            .collect();
+        writeln!(
+            out,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            line.join(" "),
+            colour
+        )?;
+        for (x, y) in points {
+            writeln!(
+                out,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="2" fill="{}"/>"#,
+                x, y, colour
+            )?;
+        }
+        let legend = TOP + LEGEND_LINE * i as f64;
+        writeln!(
+            out,
+            r#"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="{3}" stroke-width="3"/><text x="{4}" y="{5}">{6}</text>"#,
+            right + 15.0,
+            legend,
+            right + 35.0,
+            colour,
+            right + 40.0,
+            legend + 4.0,
+            escape(&series.label)
+        )?;
+    }
+    Ok(())
+}
+
+/// The smallest of 1, 2 or 5 times a power of ten that is at least `at_least`.
+fn step(at_least: f64) -> f64 {
+    if at_least <= 0.0 {
+        return 1.0;
+    }
+    let power = 10f64.powf(at_least.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|multiple| multiple * power)
+        .find(|&step| step >= at_least)
+        .unwrap_or(10.0 * power)
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_TYPE_SOFTWARE: u32 = 1;
+const PERF_TYPE_HW_CACHE: u32 = 3;
+
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const PERF_COUNT_SW_PAGE_FAULTS: u64 = 2;
+
+const PERF_COUNT_HW_CACHE_L1D: u64 = 0;
+const PERF_COUNT_HW_CACHE_LL: u64 = 2;
+const PERF_COUNT_HW_CACHE_DTLB: u64 = 3;
+/// The read operation, which is 0, and the miss result of a cache event, as `perf_event_open(2)`
+/// combines them.
+const READ_MISSES: u64 = 1 << 16;
+
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+
+const DISABLED: u64 = 1 << 0;
+const EXCLUDE_KERNEL: u64 = 1 << 5;
+const EXCLUDE_HV: u64 = 1 << 6;
+
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+/// The first version of `struct perf_event_attr`, which every kernel accepts.
+#[repr(C)]
+#[derive(Default)]
+struct Attributes {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+pub struct Event {
+    /// The key of the event in the results.
+    pub name: &'static str,
+    /// The heading of the event in the table.
+    pub heading: &'static str,
+    kind: u32,
+    config: u64,
+}
+
+/// The events `--counters` counts, in the order the reports list them.
+pub const EVENTS: &[Event] = &[
+    Event {
+        name: "cycles",
+        heading: "cycles",
+        kind: PERF_TYPE_HARDWARE,
+        config: PERF_COUNT_HW_CPU_CYCLES,
+    },
+    Event {
+        name: "instructions",
+        heading: "instrs",
+        kind: PERF_TYPE_HARDWARE,
+        config: PERF_COUNT_HW_INSTRUCTIONS,
+    },
+    Event {
+        name: "l1d_misses",
+        heading: "L1D miss",
+        kind: PERF_TYPE_HW_CACHE,
+        config: PERF_COUNT_HW_CACHE_L1D | READ_MISSES,
+    },
+    Event {
+        name: "llc_misses",
+        heading: "LLC miss",
+        kind: PERF_TYPE_HW_CACHE,
+        config: PERF_COUNT_HW_CACHE_LL | READ_MISSES,
+    },
+    Event {
+        name: "dtlb_misses",
+        heading: "dTLB miss",
+        kind: PERF_TYPE_HW_CACHE,
+        config: PERF_COUNT_HW_CACHE_DTLB | READ_MISSES,
+    },
+    Event {
+        name: "page_faults",
+        heading: "faults",
+        kind: PERF_TYPE_SOFTWARE,
+        config: PERF_COUNT_SW_PAGE_FAULTS,
+    },
+];
+
+/// The counters of the events the kernel lets the calling thread count. Without a performance
+/// monitoring unit, such as in many virtual machines, only the software events remain.
+pub struct Counters {
+    counters: Vec<(&'static Event, File)>,
+}
+
+impl Counters {
+    /// Opens the counters, disabled.
+    pub fn open() -> Counters {
+        let counters = EVENTS
+            .iter()
+            .filter_map(|event| open(event).ok().map(|file| (event, file)))
+            .collect();
+        Counters { counters }
+    }
+
+    /// Counts nothing.
+    pub fn none() -> Counters {
+        Counters {
+            counters: Vec::new(),
+        }
+    }
+
+    /// The events that cannot be counted.
+    pub fn missing(&self) -> Vec<&'static str> {
+        EVENTS
+            .iter()
+            .filter(|event| {
+                !self
+                    .counters
+                    .iter()
+                    .any(|(counted, _)| counted.name == event.name)
+            })
+            .map(|event| event.name)
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.ioctl(PERF_EVENT_IOC_RESET);
+    }
+
+    pub fn enable(&self) {
+        self.ioctl(PERF_EVENT_IOC_ENABLE);
+    }
+
+    pub fn disable(&self) {
+        self.ioctl(PERF_EVENT_IOC_DISABLE);
+    }
+
+    fn ioctl(&self, request: libc::c_ulong) {
+        for (_, file) in &self.counters {
+            unsafe { libc::ioctl(file.as_raw_fd(), request as _, 0) };
+        }
+    }
+
+    /// The counts since the last reset, scaled up for the time the kernel multiplexed the
+    /// counters with others.
+    pub fn read(&self) -> io::Result<BTreeMap<String, f64>> {
+        let mut counts = BTreeMap::new();
+        for (event, file) in &self.counters {
+            let mut values = [0u64; 3];
+            let length = mem::size_of_val(&values);
+            let read = unsafe { libc::read(file.as_raw_fd(), values.as_mut_ptr().cast(), length) };
+            if read != length as isize {
+                return Err(io::Error::last_os_error());
+            }
+            let [value, enabled, running] = values;
+            let count = if running == 0 {
+                0.0
+            } else {
+                value as f64 * enabled as f64 / running as f64
+            };
+            counts.insert(event.name.to_string(), count);
+        }
+        Ok(counts)
+    }
+}
+
+fn open(event: &Event) -> io::Result<File> {
+    let attributes = Attributes {
+        kind: event.kind,
+        size: mem::size_of::<Attributes>() as u32,
+        config: event.config,
+        read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+        flags: DISABLED | EXCLUDE_KERNEL | EXCLUDE_HV,
+        ..Attributes::default()
+    };
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            &attributes as *const Attributes,
+            0,
+            -1,
+            -1,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(unsafe { File::from_raw_fd(fd as i32) })
+    }
+}
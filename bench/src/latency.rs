@@ -0,0 +1,142 @@
+use crate::measure::{self, Backing};
+use crate::report::Measurement;
+use crate::size;
+use crate::tsc::Clock;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::time::Instant;
+
+/// The pointers are a cache line apart, so that every load misses whatever the line before brought
+/// in.
+pub const LINE: usize = 64;
+
+/// The smallest buffer with a cycle of pointers, of two lines.
+pub const SMALLEST: usize = 2 * LINE;
+
+/// The loads timed at each size, enough for the timer at the smallest sizes, and few enough not
+/// to take long past the caches, where each waits for the memory.
+const LOADS: usize = 1 << 22;
+
+/// The seed of the order of the lines, the same in every run.
+const SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The time a load takes when each waits for the one before, in a buffer of a size.
+#[derive(Serialize, Deserialize)]
+pub struct Latency {
+    pub size: usize,
+    /// What the buffer is mapped from, as for the copies.
+    pub backing: String,
+    pub loads: usize,
+    pub nanoseconds: f64,
+    /// The core cycles per load, with the time stamp counter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<f64>,
+}
+
+/// Chases pointers through every line of a buffer of `size` bytes in a random order, as a single
+/// cycle so that the chase never settles in part of the buffer, and times the loads.
+pub fn measure(size: usize, backing: Backing, clock: Option<Clock>) -> io::Result<Latency> {
+    let lines = size / LINE;
+    let mut buffer = measure::map(backing, size)?;
+    let start = buffer.as_mut_ptr();
+    for (line, next) in cycle(lines).into_iter().enumerate() {
+        unsafe { *(start.add(line * LINE) as *mut *const u8) = start.add(next * LINE) };
+    }
+
+    // Once around to bring the buffer into the caches as far as it fits.
+    black_box(chase(start, lines.min(LOADS)));
+    let timer = Instant::now();
+    black_box(chase(start, LOADS));
+    let nanoseconds = timer.elapsed().as_secs_f64() * 1e9 / LOADS as f64;
+    let cycles = clock.map(|clock| {
+        clock.cycles(1, || {
+            black_box(chase(start, LOADS));
+        }) / LOADS as f64
+    });
+    Ok(Latency {
+        size,
+        backing: backing.name().to_string(),
+        loads: LOADS,
+        nanoseconds,
+        cycles,
+    })
+}
+
+/// A random permutation of the lines that is a single cycle, by Sattolo's algorithm: `next[line]`
+/// follows `line`.
+fn cycle(lines: usize) -> Vec<usize> {
+    let mut next: Vec<usize> = (0..lines).collect();
+    let mut state = SEED;
+    for i in (1..lines).rev() {
+        // An xorshift, as good as needed for scattering lines.
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        next.swap(i, (state % i as u64) as usize);
+    }
+    next
+}
+
+/// Follows `loads` pointers from `start`, each load waiting for the one before.
+fn chase(start: *const u8, loads: usize) -> *const u8 {
+    let mut pointer = start;
+    for _ in 0..loads {
+        pointer = unsafe { *(pointer as *const *const u8) };
+    }
+    pointer
+}
+
+/// Reports the latency at each size and backing store, next to the most bandwidth a kernel copying
+/// between buffers of that size and backing store reaches, to read one against the other.
+pub fn report(
+    latencies: &[Latency],
+    measurements: &[Measurement],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let backings = latencies
+        .iter()
+        .any(|latency| latency.backing != latencies[0].backing);
+    let column = |backing: &str| {
+        if backings {
+            format!("  {:<9}", backing)
+        } else {
+            String::new()
+        }
+    };
+    writeln!(
+        out,
+        "{:>9}{}  {:>11}  {:>8}  {:>11}  kernel",
+        "size",
+        column("backing"),
+        "latency",
+        "cycles",
+        "bandwidth"
+    )?;
+    for latency in latencies {
+        let best = measurements
+            .iter()
+            .filter(|measurement| {
+                measurement.size == latency.size && measurement.backing == latency.backing
+            })
+            .max_by(|a, b| a.bandwidth.total_cmp(&b.bandwidth));
+        let cycles = latency
+            .cycles
+            .map_or_else(String::new, |cycles| format!("{:.1}", cycles));
+        let (bandwidth, kernel) = match best {
+            Some(best) => (format!("{:.1} GB/s", best.bandwidth), best.label()),
+            None => (String::new(), String::new()),
+        };
+        writeln!(
+            out,
+            "{:>9}{}  {:>8.1} ns  {:>8}  {:>11}  {}",
+            size::format(latency.size),
+            column(&latency.backing),
+            latency.nanoseconds,
+            cycles,
+            bandwidth,
+            kernel
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::size;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// The machine measurements were made on.
+#[derive(Serialize, Deserialize)]
+pub struct Machine {
+    /// What the reports call it, such as `Zen 2`.
+    pub name: String,
+    /// The model name of the processor, as Linux reports it.
+    pub processor: String,
+    pub caches: Vec<Cache>,
+}
+
+impl Machine {
+    /// Describes the machine running the program, naming it after its processor by default.
+    pub fn detect(name: Option<&str>) -> Machine {
+        let processor = fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|cpuinfo| {
+                cpuinfo
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(key, _)| key.trim() == "model name")
+                    .map(|(_, value)| value.trim().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        Machine {
+            name: name.map_or_else(|| processor.clone(), str::to_string),
+            processor,
+            caches: caches(),
+        }
+    }
+}
+
+/// A cache of the processor.
+#[derive(Serialize, Deserialize)]
+pub struct Cache {
+    /// Such as `L1d`, `L1i` or `L2`.
+    pub name: String,
+    pub size: usize,
+}
+
+/// The caches of the first processor, or none if Linux does not report them.
+fn caches() -> Vec<Cache> {
+    let mut caches = Vec::new();
+    for index in 0.. {
+        let directory = format!("/sys/devices/system/cpu/cpu0/cache/index{}", index);
+        let read = |name: &str| fs::read_to_string(format!("{}/{}", directory, name));
+        let (level, kind, size) = match (read("level"), read("type"), read("size")) {
+            (Ok(level), Ok(kind), Ok(size)) => (level, kind, size),
+            _ => break,
+        };
+        let suffix = match kind.trim() {
+            "Data" => "d",
+            "Instruction" => "i",
+            _ => "",
+        };
+        if let Ok(size) = size::parse(&size) {
+            caches.push(Cache {
+                name: format!("L{}{}", level.trim(), suffix),
+                size,
+            });
+        }
+    }
+    caches
+}
@@ -0,0 +1,526 @@
+mod chart;
+mod counters;
+mod latency;
+mod machine;
+mod measure;
+mod offsets;
+mod report;
+mod saturation;
+mod size;
+mod stream;
+mod sweep;
+mod topology;
+mod tsc;
+
+use counters::Counters;
+use glob::Pattern;
+use machine::Machine;
+use measure::{Backing, Routine, Sample, Settings};
+use memcpy::registry;
+use report::{Format, Measurement, Results};
+use saturation::Threads;
+use size::Sizes;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process;
+use structopt::StructOpt;
+use topology::Pair;
+use tsc::Clock;
+
+/// Times the copy kernels of the registry.
+#[derive(StructOpt)]
+#[structopt(name = "memcpy-bench")]
+struct Options {
+    /// Glob patterns selecting kernels by name, separated by commas.
+    #[structopt(long, default_value = "*", use_delimiter = true)]
+    kernels: Vec<Pattern>,
+    /// Sizes, such as 4096, 4k or 4KiB, or ranges of sizes doubling from one end to the other,
+    /// such as 4k..1M, separated by commas. The default is 4k,64k,4M, 16..2G with --sweep, 4k
+    /// with --offsets, or 16..4k with --cycles.
+    #[structopt(long, use_delimiter = true)]
+    sizes: Vec<Sizes>,
+    /// How many sizes to measure per doubling in the ranges. The default is 1, or 4 with --sweep.
+    #[structopt(long)]
+    steps: Option<usize>,
+    /// How many copies each thread makes. The default is 100, or as many as make about 64 MiB with
+    /// --offsets or --cycles, since a single small copy is over too quickly to time alone.
+    #[structopt(long, conflicts_with = "sweep")]
+    iterations: Option<usize>,
+    /// Sweeps through sizes spaced evenly on a logarithmic scale, with as many copies as make
+    /// about 64 MiB at each, and reports where the bandwidth of each kernel drops sharply next to
+    /// the sizes of the caches. The report follows the table, or goes to the standard error with
+    /// the other formats.
+    #[structopt(long)]
+    sweep: bool,
+    /// Places the source and the destination independently at every offset from 0 to 63 past the
+    /// start of a page, and at 4032, 4064, 4080, 4088 and 4095, instead of at the start. Only the
+    /// kernels accepting unaligned buffers run. --format svg draws a heatmap per kernel and size.
+    #[structopt(long, conflicts_with = "sweep")]
+    offsets: bool,
+    /// Counts cycles, instructions, L1D, LLC and dTLB misses and page faults per copy with the
+    /// performance counters, and reports them next to the times. The events the kernel does not
+    /// let the program count, such as the hardware ones in many virtual machines, are left out.
+    #[structopt(long)]
+    counters: bool,
+    /// Also times the copies in core cycles with `rdtsc` and `rdtscp`, in batches of up to 100
+    /// without the time the reads take, and reports the cycles per copy of the fastest batch and
+    /// the bytes copied per cycle. The default sizes are 16..4k then, with as many copies as make
+    /// about 64 MiB.
+    #[structopt(long)]
+    cycles: bool,
+    /// How many threads copy at once, each between its own buffers on a processor of its own
+    /// while there are enough, such as 4, or ranges of counts such as 1..8, separated by commas.
+    /// The default is 1, or every count from 1 to the number of processors with --saturation.
+    #[structopt(long, use_delimiter = true)]
+    threads: Vec<Threads>,
+    /// Runs the kernels on every count of threads from 1 to the number of processors, by default
+    /// copying 256 MiB per thread 4 times, past the caches, and reports the slowdown of each
+    /// thread next to the bandwidth of all of them, and how many threads saturate the memory with
+    /// each kernel. The report follows the table, or goes to the standard error with the other
+    /// formats.
+    #[structopt(long, conflicts_with_all = &["sweep", "offsets"])]
+    saturation: bool,
+    /// Runs each kernel alone on the first processor, then on two threads pinned to it and to an
+    /// SMT sibling, another core sharing its last level cache, a core with a last level cache of
+    /// its own and a core on another package, as far as the machine has them. Reports how much
+    /// slower each thread of each pair copies than the thread alone, by default at 16k,256k,4M
+    /// and 256M. The report follows the table, or goes to the standard error with the other
+    /// formats.
+    #[structopt(long, conflicts_with_all = &["sweep", "offsets", "saturation", "threads"])]
+    pairs: bool,
+    /// Also runs the STREAM kernels the patterns select, which read, write, copy, scale, add and
+    /// compute the triad of arrays of f64 in the widths and forms of the mov kernels, each array
+    /// the size. Reports the most bandwidth of each operation next to that of the copy kernels,
+    /// counting the bytes read and written as STREAM does, by default at 16k,256k,4M and 256M. The
+    /// report follows the table, or goes to the standard error with the other formats.
+    #[structopt(long, conflicts_with = "offsets")]
+    stream: bool,
+    /// Also runs the kernels the patterns select that reverse the bytes of each element of 16, 32
+    /// or 64 bits as they copy, such as bswap32_movbe.
+    #[structopt(long)]
+    bswap: bool,
+    /// Also chases pointers a cache line apart through a random cycle over a buffer of each size
+    /// and backing store, and reports the nanoseconds and core cycles per load next to the most
+    /// bandwidth a kernel reaches at that size. --format svg draws the latency under the bandwidth,
+    /// on the same size axis. The report follows the table, or goes to the standard error with the
+    /// other formats.
+    #[structopt(long, conflicts_with = "offsets")]
+    latency: bool,
+    /// Flushes the buffers from the caches before each copy, like a first copy finds them. With
+    /// --warm as well, measures both ways and reports them side by side.
+    #[structopt(long)]
+    cold: bool,
+    /// Copies between buffers already in the caches, the default.
+    #[structopt(long)]
+    warm: bool,
+    /// What to map the buffers from: anonymous memory, a memfd, a file in /dev/shm or a file in
+    /// the current directory, as anonymous, memfd, tmpfs or file, separated by commas. The files
+    /// are shared mappings, whose dirty pages the kernel tracks, and writes back for the files on
+    /// disk.
+    #[structopt(long, default_value = "anonymous", use_delimiter = true)]
+    backing: Vec<Backing>,
+    /// The kernel the times are relative to. It is run even if it is not selected.
+    #[structopt(long, default_value = "rep_movsb")]
+    baseline: String,
+    /// table, csv, json, svg for a chart of the bandwidth against the size, or markdown for
+    /// tables with a column per machine.
+    #[structopt(long, default_value = "table")]
+    format: Format,
+    /// What the results call the machine. The default is the model name of its processor.
+    #[structopt(long)]
+    machine: Option<String>,
+    /// Reads the measurements of the selected kernels from files written with --format json
+    /// instead of measuring, such as to chart them or report their cliffs on another machine.
+    /// Only --format markdown takes several files, one per machine.
+    #[structopt(
+        long,
+        parse(from_os_str),
+        conflicts_with_all = &[
+            "sizes", "steps", "iterations", "threads", "cold", "warm", "baseline", "machine",
+            "offsets", "cycles", "backing",
+        ]
+    )]
+    input: Vec<PathBuf>,
+    /// Replaces the lines between `<!-- results -->` and `<!-- /results -->` in this file, such
+    /// as the README, with the report instead of writing it to the standard output.
+    #[structopt(long, parse(from_os_str))]
+    update: Option<PathBuf>,
+}
+
+fn main() {
+    let options = Options::from_args();
+    if let Err(error) = run(&options) {
+        eprintln!("memcpy-bench: {}", error);
+        process::exit(1);
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let runs = if options.input.is_empty() {
+        vec![measure_all(options)?]
+    } else {
+        let runs = options
+            .input
+            .iter()
+            .map(|path| load(path, &options.kernels))
+            .collect::<Result<Vec<Results>, String>>()?;
+        let reported = options.sweep
+            || options.saturation
+            || options.pairs
+            || options.stream
+            || options.latency;
+        if runs.len() > 1 && (reported || !matches!(options.format, Format::Markdown)) {
+            return Err(
+                "only --format markdown without --sweep, --saturation, --pairs, --stream or \
+                 --latency reads several inputs"
+                    .to_string(),
+            );
+        }
+        runs
+    };
+
+    let mut report = Vec::new();
+    report::write(&options.format, &runs, &mut report).map_err(|e| e.to_string())?;
+    if options.sweep {
+        let (measurements, caches) = (&runs[0].measurements, &runs[0].machine.caches);
+        let result = match options.format {
+            Format::Table => {
+                writeln!(report).and_then(|()| sweep::report(measurements, caches, &mut report))
+            }
+            _ => sweep::report(measurements, caches, &mut io::stderr()),
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+    if options.saturation {
+        let measurements = &runs[0].measurements;
+        let result = match options.format {
+            Format::Table => {
+                writeln!(report).and_then(|()| saturation::report(measurements, &mut report))
+            }
+            _ => saturation::report(measurements, &mut io::stderr()),
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+    if options.pairs {
+        let measurements = &runs[0].measurements;
+        let result = match options.format {
+            Format::Table => {
+                writeln!(report).and_then(|()| topology::report(measurements, &mut report))
+            }
+            _ => topology::report(measurements, &mut io::stderr()),
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+    if options.stream {
+        let measurements = &runs[0].measurements;
+        let result = match options.format {
+            Format::Table => {
+                writeln!(report).and_then(|()| stream::report(measurements, &mut report))
+            }
+            _ => stream::report(measurements, &mut io::stderr()),
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+    if options.latency {
+        let (latencies, measurements) = (&runs[0].latencies, &runs[0].measurements);
+        let result = match options.format {
+            Format::Table => writeln!(report)
+                .and_then(|()| latency::report(latencies, measurements, &mut report)),
+            Format::Svg => Ok(()),
+            _ => latency::report(latencies, measurements, &mut io::stderr()),
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+    match &options.update {
+        Some(path) => update(path, &report),
+        None => io::stdout().write_all(&report).map_err(|e| e.to_string()),
+    }
+}
+
+fn measure_all(options: &Options) -> Result<Results, String> {
+    let steps = options
+        .steps
+        .unwrap_or(if options.sweep { sweep::STEPS } else { 1 });
+    if options.iterations == Some(0) || steps == 0 {
+        return Err("there must be at least one iteration and step".to_string());
+    }
+    let default = if options.sweep {
+        sweep::SIZES
+    } else if options.offsets {
+        offsets::SIZES
+    } else if options.cycles {
+        "16..4k"
+    } else if options.saturation {
+        saturation::SIZES
+    } else if options.pairs {
+        topology::SIZES
+    } else if options.stream {
+        stream::SIZES
+    } else {
+        "4k,64k,4M"
+    };
+    let default: Vec<Sizes> = default.split(',').map(|s| s.parse().unwrap()).collect();
+    let ranges = if options.sizes.is_empty() {
+        &default
+    } else {
+        &options.sizes
+    };
+    let baseline = registry::find(&options.baseline)
+        .map(Routine::Copy)
+        .ok_or_else(|| format!("there is no kernel named `{}`", options.baseline))?;
+    let streams: &[_] = if options.stream {
+        registry::STREAMS
+    } else {
+        &[]
+    };
+    let swaps: &[_] = if options.bswap { registry::SWAPS } else { &[] };
+    let mut kernels: Vec<Routine> = registry::KERNELS
+        .iter()
+        .chain(swaps)
+        .map(Routine::Copy)
+        .chain(streams.iter().map(Routine::Stream))
+        .filter(|kernel| {
+            options
+                .kernels
+                .iter()
+                .any(|pattern| pattern.matches(kernel.name()))
+        })
+        .collect();
+    if kernels.is_empty() {
+        return Err("no kernel matches".to_string());
+    }
+    let placements: Vec<(usize, usize)> = if options.offsets {
+        kernels.retain(|kernel| {
+            let unaligned = kernel.alignment() == 1;
+            if !unaligned {
+                eprintln!(
+                    "memcpy-bench: skipping {}, which needs aligned buffers",
+                    kernel.name()
+                );
+            }
+            unaligned
+        });
+        let offsets = offsets::offsets();
+        offsets
+            .iter()
+            .flat_map(|&source| {
+                offsets
+                    .iter()
+                    .map(move |&destination| (source, destination))
+            })
+            .collect()
+    } else {
+        vec![(0, 0)]
+    };
+
+    if options.counters {
+        let missing = Counters::open().missing();
+        if !missing.is_empty() {
+            eprintln!("memcpy-bench: cannot count {} here", missing.join(", "));
+        }
+    }
+    let clock = if options.cycles || options.latency {
+        match Clock::calibrate() {
+            Ok(clock) => {
+                eprintln!(
+                    "memcpy-bench: {:.2} core cycles per tick of the time stamp counter, {} ticks to read it",
+                    clock.ratio, clock.overhead
+                );
+                Some(clock)
+            }
+            Err(error) if options.cycles => return Err(error),
+            // The latencies are timed in nanoseconds alone then.
+            Err(_) => None,
+        }
+    } else {
+        None
+    };
+    let colds = match (options.warm, options.cold) {
+        (true, true) => vec![false, true],
+        (_, cold) => vec![cold],
+    };
+    let pairs = if options.pairs {
+        let pairs = topology::pairs();
+        if pairs.is_empty() {
+            return Err("there are no two processors to pin a pair of threads to".to_string());
+        }
+        for pair in &pairs {
+            let (first, second) = pair.processors;
+            eprintln!(
+                "memcpy-bench: pinning {} to processors {} and {}",
+                pair.name, first, second
+            );
+        }
+        pairs
+    } else {
+        Vec::new()
+    };
+    // The counts of threads, with the pair of processors to pin two threads to, if any. A thread
+    // alone runs on the first processor of the pairs.
+    let threads: Vec<(usize, Option<&Pair>)> = if options.pairs {
+        iter::once((1, None))
+            .chain(pairs.iter().map(|pair| (2, Some(pair))))
+            .collect()
+    } else {
+        let counts: Vec<usize> = match &options.threads[..] {
+            [] if options.saturation => Threads::all().expand().collect(),
+            [] => vec![1],
+            threads => threads.iter().flat_map(Threads::expand).collect(),
+        };
+        counts.into_iter().map(|count| (count, None)).collect()
+    };
+    let alone = pairs
+        .first()
+        .map(|pair| (pair.processors.0, pair.processors.0));
+    let (placements, colds) = (&placements, &colds);
+
+    let mut measurements = Vec::new();
+    let mut latencies = Vec::new();
+    for size in ranges.iter().flat_map(|range| range.expand(steps)) {
+        if options.latency {
+            if size < latency::SMALLEST {
+                eprintln!(
+                    "memcpy-bench: skipping the latency for {}, under two cache lines",
+                    size::format(size)
+                );
+            } else {
+                for &backing in &options.backing {
+                    let latency = latency::measure(size, backing, clock)
+                        .map_err(|error| format!("cannot measure the latency: {}", error))?;
+                    latencies.push(latency);
+                }
+            }
+        }
+        let iterations = match options.iterations {
+            Some(iterations) => iterations,
+            None if options.sweep
+                || options.offsets
+                || options.cycles
+                || options.pairs
+                || options.stream =>
+            {
+                (sweep::BUDGET / size).max(1)
+            }
+            None if options.saturation => saturation::ITERATIONS,
+            None => 100,
+        };
+        let settings = threads.iter().flat_map(|&(threads, pair)| {
+            options.backing.iter().flat_map(move |&backing| {
+                placements
+                    .iter()
+                    .flat_map(move |&(source_offset, destination_offset)| {
+                        colds.iter().map(move |&cold| {
+                            let settings = Settings {
+                                iterations,
+                                threads,
+                                cold,
+                                source_offset,
+                                destination_offset,
+                                counters: options.counters,
+                                cycles: clock.filter(|_| options.cycles),
+                                backing,
+                                processors: pair.map(|pair| pair.processors).or(alone),
+                            };
+                            (pair.map(|pair| pair.name), settings)
+                        })
+                    })
+            })
+        });
+        for (i, (pair, settings)) in settings.enumerate() {
+            let baseline_sample = if baseline.is_usable(size, &settings) {
+                Some(measure(baseline, size, &settings)?)
+            } else {
+                None
+            };
+            let baseline_nanoseconds = baseline_sample.as_ref().map(|sample| sample.nanoseconds);
+            for &kernel in &kernels {
+                if !kernel.is_usable(size, &settings) {
+                    // Once per size, since the kernels left accept every placement and count of
+                    // threads alike.
+                    if i == 0 {
+                        eprintln!(
+                            "memcpy-bench: skipping {} for {}",
+                            kernel.name(),
+                            size::format(size)
+                        );
+                    }
+                    continue;
+                }
+                let sample = match &baseline_sample {
+                    Some(sample) if kernel.name() == baseline.name() => sample.clone(),
+                    _ => measure(kernel, size, &settings)?,
+                };
+                let nanoseconds = sample.nanoseconds;
+                measurements.push(Measurement {
+                    kernel: kernel.name().to_string(),
+                    size,
+                    source_offset: settings.source_offset,
+                    destination_offset: settings.destination_offset,
+                    threads: settings.threads,
+                    pair: pair.map(str::to_string),
+                    cache: if settings.cold { "cold" } else { "warm" }.to_string(),
+                    backing: settings.backing.name().to_string(),
+                    iterations: settings.iterations,
+                    nanoseconds,
+                    bandwidth: (size * settings.threads) as f64 / nanoseconds,
+                    relative: baseline_nanoseconds.map(|baseline| 100.0 * nanoseconds / baseline),
+                    cycles: sample.cycles,
+                    bytes_per_cycle: sample.cycles.map(|cycles| size as f64 / cycles),
+                    slowdown: None,
+                    counters: sample.counters,
+                });
+            }
+        }
+    }
+    saturation::slowdowns(&mut measurements);
+    Ok(Results {
+        machine: Machine::detect(options.machine.as_deref()),
+        baseline: baseline.name().to_string(),
+        measurements,
+        latencies,
+        note: None,
+    })
+}
+
+/// The results in a file written with `--format json`, without the kernels the patterns do not
+/// match.
+fn load(path: &Path, patterns: &[Pattern]) -> Result<Results, String> {
+    let mut results: Results = File::open(path)
+        .map_err(|error| error.to_string())
+        .and_then(|file| {
+            serde_json::from_reader(io::BufReader::new(file)).map_err(|error| error.to_string())
+        })
+        .map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+    results.measurements.retain(|measurement| {
+        patterns
+            .iter()
+            .any(|pattern| pattern.matches(&measurement.kernel))
+    });
+    Ok(results)
+}
+
+/// Replaces the lines between the markers in the file with the report.
+fn update(path: &Path, report: &[u8]) -> Result<(), String> {
+    const START: &str = "<!-- results -->\n";
+    const END: &str = "<!-- /results -->\n";
+    let error = |error: String| format!("cannot update {}: {}", path.display(), error);
+    let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let start = text
+        .find(START)
+        .map(|start| start + START.len())
+        .ok_or_else(|| error(format!("there is no `{}` line", START.trim())))?;
+    let end = text[start..]
+        .find(END)
+        .map(|end| start + end)
+        .ok_or_else(|| error(format!("there is no `{}` line after it", END.trim())))?;
+    let report = String::from_utf8_lossy(report);
+    let text = format!("{}{}{}", &text[..start], report, &text[end..]);
+    fs::write(path, text).map_err(|e| error(e.to_string()))
+}
+
+fn measure(kernel: Routine, size: usize, settings: &Settings) -> Result<Sample, String> {
+    measure::measure(kernel, size, settings)
+        .map_err(|error| format!("cannot measure {}: {}", kernel.name(), error))
+}
@@ -0,0 +1,363 @@
+use crate::counters::Counters;
+use crate::tsc::Clock;
+use memcpy::cache;
+use memcpy::registry::{Kernel, Stream};
+use memmap::MmapMut;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::hint::black_box;
+use std::io;
+use std::mem;
+use std::os::unix::io::FromRawFd;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy)]
+pub struct Settings {
+    pub iterations: usize,
+    pub threads: usize,
+    /// Whether the buffers are flushed from the caches before each copy.
+    pub cold: bool,
+    /// How far past the start of a page the source starts.
+    pub source_offset: usize,
+    /// How far past the start of a page the destination starts.
+    pub destination_offset: usize,
+    /// Whether to count the events of the performance counters during the copies.
+    pub counters: bool,
+    /// The clock to time the copies in core cycles with as well, if any.
+    pub cycles: Option<Clock>,
+    /// What the buffers are mapped from.
+    pub backing: Backing,
+    /// The processors to pin the first two threads to, instead of the first the program may run
+    /// on.
+    pub processors: Option<(usize, usize)>,
+}
+
+/// What the buffers are mapped from. The kernel tracks the pages of the shared mappings that the
+/// copies dirty, and writes those of files on disk back.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Backing {
+    Anonymous,
+    /// A file of `memfd_create(2)`, as shared between processes.
+    Memfd,
+    /// A file in `/dev/shm`.
+    Tmpfs,
+    /// A file in the current directory.
+    File,
+}
+
+impl Backing {
+    pub fn name(self) -> &'static str {
+        match self {
+            Backing::Anonymous => "anonymous",
+            Backing::Memfd => "memfd",
+            Backing::Tmpfs => "tmpfs",
+            Backing::File => "file",
+        }
+    }
+}
+
+impl FromStr for Backing {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Backing, String> {
+        match text {
+            "anonymous" => Ok(Backing::Anonymous),
+            "memfd" => Ok(Backing::Memfd),
+            "tmpfs" => Ok(Backing::Tmpfs),
+            "file" => Ok(Backing::File),
+            _ => Err(format!(
+                "`{}` is not one of anonymous, memfd, tmpfs or file",
+                text
+            )),
+        }
+    }
+}
+
+/// A kernel to time: a copy kernel, or one of the STREAM kernels over three arrays.
+#[derive(Clone, Copy)]
+pub enum Routine {
+    Copy(&'static Kernel),
+    Stream(&'static Stream),
+}
+
+impl Routine {
+    pub fn name(self) -> &'static str {
+        match self {
+            Routine::Copy(kernel) => kernel.name,
+            Routine::Stream(stream) => stream.name,
+        }
+    }
+
+    pub fn alignment(self) -> usize {
+        match self {
+            Routine::Copy(kernel) => kernel.alignment,
+            Routine::Stream(stream) => stream.alignment,
+        }
+    }
+
+    /// Whether the processor has the kernel's features and the kernel accepts the size and the
+    /// placement. The buffers start in pages of their own.
+    pub fn is_usable(self, size: usize, settings: &Settings) -> bool {
+        let source = (4096 + settings.source_offset) as *const u8;
+        let destination = (4096 + settings.destination_offset) as *const u8;
+        match self {
+            Routine::Copy(kernel) => {
+                kernel.is_supported() && kernel.accepts(size, source, destination)
+            }
+            Routine::Stream(stream) => {
+                stream.is_supported() && stream.accepts(size, source, destination, destination)
+            }
+        }
+    }
+}
+
+/// How many copies are timed at once in core cycles: few enough for most batches to run without
+/// an interrupt, and enough for the small copies to outlast reading the counter.
+const BATCH: usize = 100;
+
+#[derive(Clone)]
+pub struct Sample {
+    /// The time per copy of the slowest thread.
+    pub nanoseconds: f64,
+    /// The core cycles per copy in the fastest batch of the slowest thread, if asked.
+    pub cycles: Option<f64>,
+    /// The counts per copy of the events the kernel lets the threads count, if asked.
+    pub counters: BTreeMap<String, f64>,
+}
+
+/// Times `iterations` copies of `size` bytes with `routine` on every thread at once, each pinned to
+/// a processor of its own while there are enough, and to the same ones again after.
+pub fn measure(routine: Routine, size: usize, settings: &Settings) -> io::Result<Sample> {
+    let barrier = Arc::new(Barrier::new(settings.threads));
+    let processors = match settings.processors {
+        Some((first, second)) => vec![first, second],
+        None => processors(),
+    };
+    let threads: Vec<_> = (0..settings.threads)
+        .map(|i| {
+            let barrier = barrier.clone();
+            let settings = *settings;
+            let processor = processors.get(i % processors.len().max(1)).copied();
+            thread::spawn(move || {
+                // Before mapping the buffers, so that they come from the processor's memory.
+                if let Some(processor) = processor {
+                    pin(processor);
+                }
+                run(routine, size, &settings, &barrier)
+            })
+        })
+        .collect();
+
+    let copies = (settings.iterations * settings.threads) as f64;
+    let mut slowest = Duration::from_secs(0);
+    let mut cycles: Option<f64> = None;
+    let mut counters = BTreeMap::new();
+    for thread in threads {
+        let (elapsed, fastest, counts) = thread.join().unwrap()?;
+        slowest = slowest.max(elapsed);
+        cycles = fastest.map(|fastest| cycles.map_or(fastest, |cycles| cycles.max(fastest)));
+        for (event, count) in counts {
+            *counters.entry(event).or_insert(0.0) += count / copies;
+        }
+    }
+    Ok(Sample {
+        nanoseconds: slowest.as_secs_f64() * 1e9 / settings.iterations as f64,
+        cycles,
+        counters,
+    })
+}
+
+/// The time, core cycles per copy of the fastest batch, and counts of the events of a thread.
+type Times = (Duration, Option<f64>, BTreeMap<String, f64>);
+
+/// The times of the copies of a thread.
+fn run(routine: Routine, size: usize, settings: &Settings, barrier: &Barrier) -> io::Result<Times> {
+    // Every thread waits for the others, even when it cannot map its buffers.
+    let buffers = buffers(routine, size, settings);
+    barrier.wait();
+    let mut buffers = buffers?;
+    let pointers: Vec<*mut u8> = buffers
+        .iter_mut()
+        .map(|(buffer, offset)| unsafe { buffer.as_mut_ptr().add(*offset) })
+        .collect();
+    // Keeps the compiler from inlining the kernel and hoisting the copy out of the loop.
+    match routine {
+        Routine::Copy(kernel) => {
+            let copy = black_box(kernel.copy);
+            let (source, destination) = (pointers[0], pointers[1]);
+            time(
+                || copy(size, source, destination),
+                &pointers,
+                size,
+                settings,
+            )
+        }
+        Routine::Stream(stream) => {
+            let run = black_box(stream.run);
+            let (a, b, c) = (pointers[0], pointers[1], pointers[2]);
+            time(|| run(size, a, b, c), &pointers, size, settings)
+        }
+    }
+}
+
+/// Times `iterations` calls of `copy` on the buffers.
+fn time(
+    mut copy: impl FnMut(),
+    buffers: &[*mut u8],
+    size: usize,
+    settings: &Settings,
+) -> io::Result<Times> {
+    // Without any, enabling and disabling the counters costs nothing.
+    let counters = if settings.counters {
+        Counters::open()
+    } else {
+        Counters::none()
+    };
+    counters.reset();
+    let mut fastest = f64::INFINITY;
+    let mut batch = |copies: usize| match settings.cycles {
+        Some(clock) => {
+            let cycles = clock.cycles(copies, &mut copy);
+            fastest = fastest.min(cycles);
+        }
+        None => {
+            for _ in 0..copies {
+                copy();
+            }
+        }
+    };
+
+    let elapsed = if settings.cold {
+        let flush = cache::flusher();
+        let mut elapsed = Duration::from_secs(0);
+        for _ in 0..settings.iterations {
+            for &buffer in buffers {
+                flush(buffer, size);
+            }
+            counters.enable();
+            let start = Instant::now();
+            batch(1);
+            elapsed += start.elapsed();
+            counters.disable();
+        }
+        elapsed
+    } else {
+        counters.enable();
+        let start = Instant::now();
+        let mut left = settings.iterations;
+        while left > 0 {
+            let copies = left.min(BATCH);
+            batch(copies);
+            left -= copies;
+        }
+        let elapsed = start.elapsed();
+        counters.disable();
+        elapsed
+    };
+    let cycles = settings.cycles.map(|_| fastest);
+    Ok((elapsed, cycles, counters.read()?))
+}
+
+/// The processors the program may run on, or none if Linux does not say.
+fn processors() -> Vec<usize> {
+    unsafe {
+        let mut set: libc::cpu_set_t = mem::zeroed();
+        if libc::sched_getaffinity(0, mem::size_of_val(&set), &mut set) != 0 {
+            return Vec::new();
+        }
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|&processor| libc::CPU_ISSET(processor, &set))
+            .collect()
+    }
+}
+
+/// Keeps the calling thread on the processor. It runs anywhere still if Linux refuses.
+fn pin(processor: usize) {
+    unsafe {
+        let mut set: libc::cpu_set_t = mem::zeroed();
+        libc::CPU_SET(processor, &mut set);
+        libc::sched_setaffinity(0, mem::size_of_val(&set), &set);
+    }
+}
+
+/// The buffers of a thread, with how far into them the kernel starts: the source and the
+/// destination of a copy, or the arrays a, b and c of STREAM, which start at the offsets of the
+/// source, the destination and the destination.
+fn buffers(
+    routine: Routine,
+    size: usize,
+    settings: &Settings,
+) -> io::Result<Vec<(MmapMut, usize)>> {
+    let (source, destination) = (settings.source_offset, settings.destination_offset);
+    let map = |offset: usize| map(settings.backing, offset + size).map(|buffer| (buffer, offset));
+    match routine {
+        Routine::Copy(kernel) => {
+            let (mut source, mut destination) = (map(source)?, map(destination)?);
+            for (i, byte) in source.0.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+            // The first copy faults the destination in.
+            unsafe {
+                (kernel.copy)(
+                    size,
+                    source.0.as_mut_ptr().add(source.1),
+                    destination.0.as_mut_ptr().add(destination.1),
+                )
+            };
+            Ok(vec![source, destination])
+        }
+        Routine::Stream(_) => {
+            // Filled with 1, 2 and 0 as STREAM fills them, which the kernels turn into nothing
+            // slower to compute with, such as subnormal numbers, however often they run.
+            let mut arrays = vec![map(source)?, map(destination)?, map(destination)?];
+            for ((array, _), value) in arrays.iter_mut().zip([1.0f64, 2.0, 0.0].iter()) {
+                for element in array.chunks_exact_mut(8) {
+                    element.copy_from_slice(&value.to_ne_bytes());
+                }
+            }
+            Ok(arrays)
+        }
+    }
+}
+
+pub fn map(backing: Backing, length: usize) -> io::Result<MmapMut> {
+    let file = match backing {
+        Backing::Anonymous => return MmapMut::map_anon(length),
+        Backing::Memfd => {
+            let name = b"memcpy-bench\0";
+            let fd = unsafe { libc::memfd_create(name.as_ptr().cast(), libc::MFD_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            unsafe { File::from_raw_fd(fd) }
+        }
+        Backing::Tmpfs => temporary(Path::new("/dev/shm"))?,
+        Backing::File => temporary(Path::new("."))?,
+    };
+    file.set_len(length as u64)?;
+    unsafe { MmapMut::map_mut(&file) }
+}
+
+/// Creates a file in the directory, removing it at once so that it goes away with the mapping.
+fn temporary(directory: &Path) -> io::Result<File> {
+    static FILES: AtomicUsize = AtomicUsize::new(0);
+    let name = format!(
+        ".memcpy-bench-{}-{}",
+        process::id(),
+        FILES.fetch_add(1, Ordering::Relaxed)
+    );
+    let path = directory.join(name);
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    fs::remove_file(&path)?;
+    Ok(file)
+}
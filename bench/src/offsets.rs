@@ -0,0 +1,261 @@
+use crate::chart::escape;
+use crate::report::Measurement;
+use crate::size;
+use std::io::{self, Write};
+
+/// The sizes `--offsets` covers by default.
+pub const SIZES: &str = "4k";
+
+/// The offsets past the start of a page that `--offsets` places the source and the destination
+/// at, independently: every offset within a cache line, and a few just before the next page, so
+/// that the destination also lands a little before the source modulo 4 KiB, where loads can
+/// falsely depend on earlier stores.
+pub fn offsets() -> Vec<usize> {
+    (0..64).chain(vec![4032, 4064, 4080, 4088, 4095]).collect()
+}
+
+const CELL: f64 = 5.0;
+const COLUMNS: usize = 2;
+const WIDTH: f64 = 480.0;
+const LEFT: f64 = 50.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 50.0;
+
+/// A perceptually uniform scale from slow to fast.
+const SCALE: &[(u8, u8, u8)] = &[
+    (0x44, 0x01, 0x54),
+    (0x3b, 0x52, 0x8b),
+    (0x21, 0x91, 0x8c),
+    (0x5e, 0xc9, 0x62),
+    (0xfd, 0xe7, 0x25),
+];
+
+/// Whether the measurements are of several placements, which `--format svg` draws as heatmaps.
+pub fn varied(measurements: &[Measurement]) -> bool {
+    measurements
+        .iter()
+        .any(|measurement| (measurement.source_offset, measurement.destination_offset) != (0, 0))
+}
+
+/// Writes a self-contained SVG document with a heatmap of the bandwidth by source and
+/// destination offset for every kernel, size, state of the caches and backing store, two to a
+/// row. Each heatmap has its own scale, from its slowest to its fastest placement.
+pub fn heatmaps(measurements: &[Measurement], out: &mut dyn Write) -> io::Result<()> {
+    let mut groups: Vec<(&Measurement, Vec<&Measurement>)> = Vec::new();
+    for measurement in measurements {
+        let same = |first: &Measurement| {
+            first.kernel == measurement.kernel
+                && first.size == measurement.size
+                && first.cache == measurement.cache
+                && first.backing == measurement.backing
+        };
+        match groups.iter_mut().find(|(first, _)| same(first)) {
+            Some((_, group)) => group.push(measurement),
+            None => groups.push((measurement, vec![measurement])),
+        }
+    }
+
+    let height = |group: &[&Measurement]| {
+        let rows = axis(
+            group
+                .iter()
+                .map(|measurement| measurement.destination_offset),
+        );
+        TOP + extent(&rows) + BOTTOM
+    };
+    let rows: Vec<f64> = groups
+        .chunks(COLUMNS)
+        .map(|row| {
+            row.iter()
+                .map(|(_, group)| height(group))
+                .fold(0.0, f64::max)
+        })
+        .collect();
+    let total: f64 = rows.iter().sum();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="12">"#,
+        WIDTH * COLUMNS as f64,
+        total
+    )?;
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    writeln!(
+        out,
+        r#"<defs><linearGradient id="scale" x1="0" y1="1" x2="0" y2="0">"#
+    )?;
+    for (i, &colour) in SCALE.iter().enumerate() {
+        writeln!(
+            out,
+            r#"<stop offset="{}" stop-color="{}"/>"#,
+            i as f64 / (SCALE.len() - 1) as f64,
+            hex(colour)
+        )?;
+    }
+    writeln!(out, "</linearGradient></defs>")?;
+
+    let mut y = 0.0;
+    for (row, height) in groups.chunks(COLUMNS).zip(rows) {
+        for (column, (first, group)) in row.iter().enumerate() {
+            writeln!(
+                out,
+                r#"<g transform="translate({},{})">"#,
+                WIDTH * column as f64,
+                y
+            )?;
+            heatmap(first, group, out)?;
+            writeln!(out, "</g>")?;
+        }
+        y += height;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// The distinct offsets in increasing order, with where each starts along the axis. A gap
+/// separates offsets more than a cache line apart.
+fn axis(offsets: impl Iterator<Item = usize>) -> Vec<(usize, f64)> {
+    let mut offsets: Vec<usize> = offsets.collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    let mut axis = Vec::new();
+    let mut position = 0.0;
+    for (i, &offset) in offsets.iter().enumerate() {
+        if i > 0 && offset - offsets[i - 1] > 64 {
+            position += CELL;
+        }
+        axis.push((offset, position));
+        position += CELL;
+    }
+    axis
+}
+
+fn extent(axis: &[(usize, f64)]) -> f64 {
+    axis.last().map_or(0.0, |&(_, position)| position + CELL)
+}
+
+fn heatmap(first: &Measurement, group: &[&Measurement], out: &mut dyn Write) -> io::Result<()> {
+    let columns = axis(group.iter().map(|measurement| measurement.source_offset));
+    let rows = axis(
+        group
+            .iter()
+            .map(|measurement| measurement.destination_offset),
+    );
+    let position = |axis: &[(usize, f64)], offset| {
+        axis.iter()
+            .find(|&&(o, _)| o == offset)
+            .map_or(0.0, |&(_, position)| position)
+    };
+    let bandwidths = group.iter().map(|measurement| measurement.bandwidth);
+    let slowest = bandwidths.clone().fold(f64::INFINITY, f64::min);
+    let fastest = bandwidths.fold(0.0, f64::max);
+
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="14" font-weight="bold">{}, {}{}{}</text>"#,
+        LEFT,
+        TOP - 16.0,
+        escape(&first.kernel),
+        size::format(first.size),
+        if first.cache == "warm" { "" } else { ", cold" },
+        match first.backing.as_str() {
+            "anonymous" => String::new(),
+            backing => format!(", {}", backing),
+        }
+    )?;
+    for measurement in group {
+        let fraction = if fastest > slowest {
+            (measurement.bandwidth - slowest) / (fastest - slowest)
+        } else {
+            1.0
+        };
+        writeln!(
+            out,
+            r#"<rect x="{:.1}" y="{:.1}" width="{}" height="{}" fill="{}"><title>source +{}, destination +{}: {:.2} GB/s</title></rect>"#,
+            LEFT + position(&columns, measurement.source_offset),
+            TOP + position(&rows, measurement.destination_offset),
+            CELL,
+            CELL,
+            hex(colour(fraction)),
+            measurement.source_offset,
+            measurement.destination_offset,
+            measurement.bandwidth
+        )?;
+    }
+
+    // The offsets at the start of each cache line and after each gap.
+    let (width, height) = (extent(&columns), extent(&rows));
+    let labelled = |axis: &[(usize, f64)]| -> Vec<(usize, f64)> {
+        axis.iter()
+            .enumerate()
+            .filter(|&(i, &(offset, _))| {
+                offset < 64 && offset & 15 == 0 || i > 0 && offset - axis[i - 1].0 > 64
+            })
+            .map(|(_, &label)| label)
+            .collect()
+    };
+    for (offset, position) in labelled(&columns) {
+        writeln!(
+            out,
+            r#"<text x="{:.1}" y="{}" text-anchor="middle">{}</text>"#,
+            LEFT + position + CELL / 2.0,
+            TOP + height + 14.0,
+            offset
+        )?;
+    }
+    for (offset, position) in labelled(&rows) {
+        writeln!(
+            out,
+            r#"<text x="{}" y="{:.1}" text-anchor="end">{}</text>"#,
+            LEFT - 4.0,
+            TOP + position + CELL,
+            offset
+        )?;
+    }
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}" text-anchor="middle">source offset</text>"#,
+        LEFT + width / 2.0,
+        TOP + height + 32.0
+    )?;
+    writeln!(
+        out,
+        r#"<text transform="translate({},{}) rotate(-90)" text-anchor="middle">destination offset</text>"#,
+        LEFT - 36.0,
+        TOP + height / 2.0
+    )?;
+
+    // The scale, from the slowest placement at the bottom to the fastest at the top.
+    let x = LEFT + width + 12.0;
+    writeln!(
+        out,
+        r#"<rect x="{}" y="{}" width="10" height="{}" fill="url(#scale)"/>"#,
+        x, TOP, height
+    )?;
+    for &(bandwidth, y) in &[(fastest, TOP + 10.0), (slowest, TOP + height)] {
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}">{:.1}</text>"#,
+            x + 14.0,
+            y,
+            bandwidth
+        )?;
+    }
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}">GB/s</text>"#,
+        x + 14.0,
+        TOP + height / 2.0
+    )
+}
+
+fn colour(fraction: f64) -> (u8, u8, u8) {
+    let position = fraction.clamp(0.0, 1.0) * (SCALE.len() - 1) as f64;
+    let i = (position as usize).min(SCALE.len() - 2);
+    let t = position - i as f64;
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    let ((r0, g0, b0), (r1, g1, b1)) = (SCALE[i], SCALE[i + 1]);
+    (mix(r0, r1), mix(g0, g1), mix(b0, b1))
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
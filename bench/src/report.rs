@@ -0,0 +1,565 @@
+use crate::counters::{Event, EVENTS};
+use crate::latency::Latency;
+use crate::machine::Machine;
+use crate::{chart, offsets, size};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize)]
+pub struct Measurement {
+    pub kernel: String,
+    pub size: usize,
+    /// How far past the start of a page the source starts.
+    #[serde(default)]
+    pub source_offset: usize,
+    /// How far past the start of a page the destination starts.
+    #[serde(default)]
+    pub destination_offset: usize,
+    pub threads: usize,
+    /// How the processors the two threads were pinned to relate with `--pairs`, such as
+    /// `siblings`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pair: Option<String>,
+    /// `warm` or `cold`.
+    pub cache: String,
+    /// What the buffers are mapped from: `anonymous` memory, a `memfd`, a `tmpfs` file or a
+    /// `file` on disk.
+    #[serde(default = "anonymous")]
+    pub backing: String,
+    /// The copies timed, or 0 for measurements transcribed from elsewhere that do not say.
+    #[serde(default)]
+    pub iterations: usize,
+    /// The time per copy of the slowest thread.
+    pub nanoseconds: f64,
+    /// The bytes all the threads together copy per nanosecond, or GB/s.
+    pub bandwidth: f64,
+    /// The time per copy as a percentage of the baseline's, if it accepts the size.
+    pub relative: Option<f64>,
+    /// The time per copy of the slowest thread as a multiple of the time of a single thread, if the
+    /// threads vary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowdown: Option<f64>,
+    /// The core cycles per copy in the fastest batch of the slowest thread, with `--cycles`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<f64>,
+    /// The bytes each thread copies per core cycle in its fastest batch, with `--cycles`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_per_cycle: Option<f64>,
+    /// The counts per copy of the events of the performance counters, with `--counters`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counters: BTreeMap<String, f64>,
+}
+
+fn anonymous() -> String {
+    "anonymous".to_string()
+}
+
+impl Measurement {
+    /// The kernel, followed by the state of the caches and the backing store unless they are warm
+    /// and anonymous memory.
+    pub fn label(&self) -> String {
+        let mut details = Vec::new();
+        if self.cache != "warm" {
+            details.push(self.cache.as_str());
+        }
+        if self.backing != "anonymous" {
+            details.push(self.backing.as_str());
+        }
+        if details.is_empty() {
+            self.kernel.clone()
+        } else {
+            format!("{} ({})", self.kernel, details.join(", "))
+        }
+    }
+}
+
+/// The measurements of a run of the program and where they were made, as `--format json` writes
+/// them.
+#[derive(Serialize, Deserialize)]
+pub struct Results {
+    pub machine: Machine,
+    /// The kernel the times are relative to.
+    pub baseline: String,
+    pub measurements: Vec<Measurement>,
+    /// The latency of loads at each size, with `--latency`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub latencies: Vec<Latency>,
+    /// Where the measurements come from when not from a run of the program, for the README.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+    Svg,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Format, String> {
+        match text {
+            "table" => Ok(Format::Table),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "svg" => Ok(Format::Svg),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(format!(
+                "`{}` is not one of table, csv, json, svg or markdown",
+                text
+            )),
+        }
+    }
+}
+
+/// Writes the results of one run, or of several side by side in markdown.
+pub fn write(format: &Format, runs: &[Results], out: &mut dyn Write) -> io::Result<()> {
+    let run = &runs[0];
+    match format {
+        Format::Table => table(&run.measurements, out),
+        Format::Csv => csv(&run.measurements, out),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, run)?;
+            writeln!(out)
+        }
+        Format::Svg if offsets::varied(&run.measurements) => {
+            offsets::heatmaps(&run.measurements, out)
+        }
+        Format::Svg => chart::write(&run.measurements, &run.latencies, &run.machine.caches, out),
+        Format::Markdown => markdown(runs, out),
+    }
+}
+
+/// The table has columns of offsets, threads, pairs and backing stores only if they vary, and puts
+/// the measurements with warm and cold caches side by side if there are both. The slowdowns, the
+/// cycles, then the counts of the events, follow the times of each.
+fn table(measurements: &[Measurement], out: &mut dyn Write) -> io::Result<()> {
+    let offsets = offsets::varied(measurements);
+    let threads = measurements
+        .iter()
+        .any(|measurement| measurement.threads != measurements[0].threads);
+    let pairs = measurements
+        .iter()
+        .filter_map(|measurement| measurement.pair.as_ref())
+        .map(|pair| pair.len())
+        .max();
+    let backings = backings(measurements);
+    let caches = caches(measurements);
+    let slowdowns = measurements
+        .iter()
+        .any(|measurement| measurement.slowdown.is_some());
+    let cycles = cycles(measurements);
+    let events = events(measurements);
+    let mut rows: Vec<Vec<Option<&Measurement>>> = Vec::new();
+    let mut index = HashMap::new();
+    for measurement in measurements {
+        let key = (
+            measurement.size,
+            measurement.source_offset,
+            measurement.destination_offset,
+            measurement.kernel.as_str(),
+            measurement.threads,
+            measurement.pair.as_deref(),
+            measurement.backing.as_str(),
+        );
+        let row = *index.entry(key).or_insert_with(|| {
+            rows.push(vec![None; caches.len()]);
+            rows.len() - 1
+        });
+        let column = caches.iter().position(|&cache| cache == measurement.cache);
+        rows[row][column.unwrap()] = Some(measurement);
+    }
+
+    let width = measurements
+        .iter()
+        .map(|measurement| measurement.kernel.len())
+        .max()
+        .unwrap_or(0)
+        .max("kernel".len());
+    write!(
+        out,
+        "{:>9}  {}{:<width$}{}",
+        "size",
+        if offsets { "   offsets  " } else { "" },
+        "kernel",
+        if threads { "  threads" } else { "" },
+        width = width
+    )?;
+    if let Some(width) = pairs {
+        write!(out, "  {:<width$}", "pair", width = width)?;
+    }
+    if backings {
+        write!(out, "  {:<9}", "backing")?;
+    }
+    for cache in &caches {
+        let time = if caches.len() > 1 {
+            format!("{} ns/copy", cache)
+        } else {
+            "ns/copy".to_string()
+        };
+        write!(out, "  {:>12}  {:>8}  {:>8}", time, "GB/s", "baseline")?;
+        if slowdowns {
+            write!(out, "  {:>8}", "slowdown")?;
+        }
+        if cycles {
+            write!(out, "  {:>8}  {:>8}", "cycles", "B/cycle")?;
+        }
+        for event in &events {
+            write!(out, "  {:>10}", event.heading)?;
+        }
+    }
+    writeln!(out)?;
+    let mut previous = None;
+    for row in rows {
+        let first = row.iter().flatten().next().unwrap();
+        // A blank line between sizes, counts of threads, pairs and backing stores.
+        let group = (
+            first.size,
+            first.threads,
+            first.pair.as_deref(),
+            first.backing.as_str(),
+        );
+        if previous.is_some() && previous != Some(group) {
+            writeln!(out)?;
+        }
+        previous = Some(group);
+        let placement = if offsets {
+            format!(
+                "{:>4} {:>4}  ",
+                first.source_offset, first.destination_offset
+            )
+        } else {
+            String::new()
+        };
+        let count = if threads {
+            format!("  {:>7}", first.threads)
+        } else {
+            String::new()
+        };
+        write!(
+            out,
+            "{:>9}  {}{:<width$}{}",
+            size::format(first.size),
+            placement,
+            first.kernel,
+            count,
+            width = width
+        )?;
+        if let Some(width) = pairs {
+            let pair = first.pair.as_deref().unwrap_or("");
+            write!(out, "  {:<width$}", pair, width = width)?;
+        }
+        if backings {
+            write!(out, "  {:<9}", first.backing)?;
+        }
+        for measurement in row {
+            match measurement {
+                Some(measurement) => {
+                    let relative = measurement
+                        .relative
+                        .map_or_else(String::new, |relative| format!("{:.0} %", relative));
+                    write!(
+                        out,
+                        "  {:>12.1}  {:>8.2}  {:>8}",
+                        measurement.nanoseconds, measurement.bandwidth, relative
+                    )?;
+                    if slowdowns {
+                        let slowdown = measurement
+                            .slowdown
+                            .map_or_else(String::new, |slowdown| format!("{:.2}×", slowdown));
+                        write!(out, "  {:>8}", slowdown)?;
+                    }
+                    if cycles {
+                        match (measurement.cycles, measurement.bytes_per_cycle) {
+                            (Some(cycles), Some(bytes)) => {
+                                write!(out, "  {:>8.1}  {:>8.2}", cycles, bytes)?
+                            }
+                            _ => write!(out, "  {:>8}  {:>8}", "", "")?,
+                        }
+                    }
+                    for event in &events {
+                        match measurement.counters.get(event.name) {
+                            Some(count) => write!(out, "  {:>10.1}", count)?,
+                            None => write!(out, "  {:>10}", "")?,
+                        }
+                    }
+                }
+                None => {
+                    write!(out, "  {:>12}  {:>8}  {:>8}", "", "", "")?;
+                    if slowdowns {
+                        write!(out, "  {:>8}", "")?;
+                    }
+                    if cycles {
+                        write!(out, "  {:>8}  {:>8}", "", "")?;
+                    }
+                    for _ in &events {
+                        write!(out, "  {:>10}", "")?;
+                    }
+                }
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// The caches the measurements were made with, warm first.
+fn caches<'a>(measurements: impl IntoIterator<Item = &'a Measurement>) -> Vec<&'a str> {
+    let mut caches = Vec::new();
+    for measurement in measurements {
+        if !caches.contains(&measurement.cache.as_str()) {
+            caches.push(measurement.cache.as_str());
+        }
+    }
+    caches.sort_by_key(|&cache| cache != "warm");
+    caches
+}
+
+/// Whether the measurements are of several backing stores.
+fn backings(measurements: &[Measurement]) -> bool {
+    measurements
+        .iter()
+        .any(|measurement| measurement.backing != measurements[0].backing)
+}
+
+/// Whether any of the measurements were timed in core cycles.
+fn cycles(measurements: &[Measurement]) -> bool {
+    measurements
+        .iter()
+        .any(|measurement| measurement.cycles.is_some())
+}
+
+/// The events counted in any of the measurements, in the order of `EVENTS`.
+fn events(measurements: &[Measurement]) -> Vec<&'static Event> {
+    EVENTS
+        .iter()
+        .filter(|event| {
+            measurements
+                .iter()
+                .any(|measurement| measurement.counters.contains_key(event.name))
+        })
+        .collect()
+}
+
+/// The backing stores if they vary, and the pairs, the slowdowns, the cycles and the counts of the
+/// events, if any, follow the other columns, which are named after the events.
+fn csv(measurements: &[Measurement], out: &mut dyn Write) -> io::Result<()> {
+    let backings = backings(measurements);
+    let pairs = measurements
+        .iter()
+        .any(|measurement| measurement.pair.is_some());
+    let slowdowns = measurements
+        .iter()
+        .any(|measurement| measurement.slowdown.is_some());
+    let cycles = cycles(measurements);
+    let events = events(measurements);
+    write!(
+        out,
+        "kernel,size,source_offset,destination_offset,threads,cache,iterations,nanoseconds,\
+         bandwidth,relative"
+    )?;
+    if backings {
+        write!(out, ",backing")?;
+    }
+    if pairs {
+        write!(out, ",pair")?;
+    }
+    if slowdowns {
+        write!(out, ",slowdown")?;
+    }
+    if cycles {
+        write!(out, ",cycles,bytes_per_cycle")?;
+    }
+    for event in &events {
+        write!(out, ",{}", event.name)?;
+    }
+    writeln!(out)?;
+    for measurement in measurements {
+        let relative = measurement
+            .relative
+            .map_or_else(String::new, |relative| relative.to_string());
+        write!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{}",
+            measurement.kernel,
+            measurement.size,
+            measurement.source_offset,
+            measurement.destination_offset,
+            measurement.threads,
+            measurement.cache,
+            measurement.iterations,
+            measurement.nanoseconds,
+            measurement.bandwidth,
+            relative
+        )?;
+        let optional =
+            |value: Option<f64>| value.map_or_else(String::new, |value| value.to_string());
+        if backings {
+            write!(out, ",{}", measurement.backing)?;
+        }
+        if pairs {
+            write!(out, ",{}", measurement.pair.as_deref().unwrap_or(""))?;
+        }
+        if slowdowns {
+            write!(out, ",{}", optional(measurement.slowdown))?;
+        }
+        if cycles {
+            write!(
+                out,
+                ",{},{}",
+                optional(measurement.cycles),
+                optional(measurement.bytes_per_cycle)
+            )?;
+        }
+        for event in &events {
+            let count = measurement
+                .counters
+                .get(event.name)
+                .map_or_else(String::new, |count| count.to_string());
+            write!(out, ",{}", count)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Whether a measurement is of a single thread copying between anonymous buffers at the start of
+/// their pages, the only configuration the tables of the README compare across machines.
+fn plain(measurement: &Measurement) -> bool {
+    measurement.threads == 1
+        && measurement.pair.is_none()
+        && measurement.backing == "anonymous"
+        && measurement.source_offset == 0
+        && measurement.destination_offset == 0
+}
+
+/// A table per size, and per state of the caches if both appear, with a column per machine,
+/// followed by a description of the machines. The times of a table share a unit, chosen for the
+/// fastest.
+fn markdown(runs: &[Results], out: &mut dyn Write) -> io::Result<()> {
+    let plains: Vec<Vec<&Measurement>> = runs
+        .iter()
+        .map(|run| {
+            run.measurements
+                .iter()
+                .filter(|measurement| plain(measurement))
+                .collect()
+        })
+        .collect();
+    if let Some((run, _)) = runs
+        .iter()
+        .zip(&plains)
+        .find(|(_, plains)| plains.is_empty())
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "the results of {} have no measurement of a single thread between anonymous \
+                 buffers aligned to pages",
+                run.machine.name
+            ),
+        ));
+    }
+    let mut sizes = Vec::new();
+    let mut kernels = Vec::new();
+    for &measurement in plains.iter().flatten() {
+        if !sizes.contains(&measurement.size) {
+            sizes.push(measurement.size);
+        }
+        if !kernels.contains(&measurement.kernel.as_str()) {
+            kernels.push(&measurement.kernel);
+        }
+    }
+    let all = caches(plains.iter().flatten().copied());
+    for (size, cache) in sizes
+        .iter()
+        .flat_map(|&size| all.iter().map(move |&cache| (size, cache)))
+    {
+        if all.len() > 1 {
+            writeln!(out, "#### {}, {} caches", size::format(size), cache)?;
+        } else {
+            writeln!(out, "#### {}", size::format(size))?;
+        }
+        writeln!(out)?;
+        let names: Vec<&str> = runs.iter().map(|run| run.machine.name.as_str()).collect();
+        writeln!(out, "| kernel | {} |", names.join(" | "))?;
+        writeln!(out, "| :-- |{}", " --: |".repeat(runs.len()))?;
+        let fastest = plains
+            .iter()
+            .flatten()
+            .filter(|measurement| measurement.size == size && measurement.cache == cache)
+            .map(|measurement| measurement.nanoseconds)
+            .fold(f64::INFINITY, f64::min);
+        let (unit, divisor) = if fastest < 1e4 {
+            ("ns", 1.0)
+        } else if fastest < 1e7 {
+            ("μs", 1e3)
+        } else {
+            ("ms", 1e6)
+        };
+        for &kernel in &kernels {
+            let cells: Vec<String> = plains
+                .iter()
+                .map(|measurements| {
+                    measurements.iter().find(|measurement| {
+                        measurement.kernel == kernel
+                            && measurement.size == size
+                            && measurement.cache == cache
+                    })
+                })
+                .map(|measurement| match measurement {
+                    Some(measurement) => {
+                        let time = measurement.nanoseconds / divisor;
+                        let precision = if time < 10.0 { 1 } else { 0 };
+                        let relative = measurement
+                            .relative
+                            .map_or_else(String::new, |relative| format!(" ({:.0} %)", relative));
+                        format!("{:.*} {}{}", precision, time, unit, relative)
+                    }
+                    None => String::new(),
+                })
+                .collect();
+            if cells.iter().any(|cell| !cell.is_empty()) {
+                writeln!(out, "| `{}` | {} |", kernel, cells.join(" | "))?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    writeln!(
+        out,
+        "The times are per copy by a single thread between anonymous buffers aligned to pages, with \
+         the percentage of the time of the baseline of the machine."
+    )?;
+    writeln!(out)?;
+    for (run, measurements) in runs.iter().zip(&plains) {
+        let hierarchy: Vec<String> = run
+            .machine
+            .caches
+            .iter()
+            .map(|cache| format!("{} {}", cache.name, size::format(cache.size)))
+            .collect();
+        writeln!(
+            out,
+            "- {}: {}, {}. Baseline `{}`, {} caches.{}",
+            run.machine.name,
+            run.machine.processor,
+            if hierarchy.is_empty() {
+                "caches unknown".to_string()
+            } else {
+                hierarchy.join(", ")
+            },
+            run.baseline,
+            caches(measurements.iter().copied()).join(" and "),
+            run.note
+                .as_ref()
+                .map_or_else(String::new, |note| format!(" {}", note))
+        )?;
+    }
+    Ok(())
+}
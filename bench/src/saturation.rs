@@ -0,0 +1,166 @@
+use crate::report::Measurement;
+use crate::size;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// The sizes `--saturation` covers by default, past the caches of most machines.
+pub const SIZES: &str = "256M";
+
+/// The copies each thread makes with `--saturation` by default, since a copy of 256 MiB takes
+/// long enough to time alone.
+pub const ITERATIONS: usize = 4;
+
+/// The memory is saturated once the bandwidth reaches this fraction of the most it reaches.
+const SATURATED: f64 = 0.9;
+
+/// A count of threads, or an inclusive range of counts written `start..end`.
+pub struct Threads {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Threads {
+    /// Every count from one to the number of processors.
+    pub fn all() -> Threads {
+        let processors = std::thread::available_parallelism().map_or(1, |count| count.get());
+        Threads {
+            start: 1,
+            end: processors,
+        }
+    }
+
+    pub fn expand(&self) -> impl Iterator<Item = usize> {
+        self.start..=self.end
+    }
+}
+
+impl FromStr for Threads {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Threads, String> {
+        let parse = |text: &str| match text.trim().parse() {
+            Ok(0) => Err("there must be at least one thread".to_string()),
+            Ok(count) => Ok(count),
+            Err(_) => Err(format!("`{}` is not a count of threads", text)),
+        };
+        let (start, end) = match text.find("..") {
+            Some(i) => (parse(&text[..i])?, parse(&text[i + 2..])?),
+            None => {
+                let count = parse(text)?;
+                (count, count)
+            }
+        };
+        if start > end {
+            return Err(format!("`{}` is an empty range", text));
+        }
+        Ok(Threads { start, end })
+    }
+}
+
+/// The measurements that differ only in their threads.
+type Key<'a> = (&'a str, usize, usize, usize, &'a str, &'a str);
+
+fn key(measurement: &Measurement) -> Key<'_> {
+    (
+        &measurement.kernel,
+        measurement.size,
+        measurement.source_offset,
+        measurement.destination_offset,
+        &measurement.cache,
+        &measurement.backing,
+    )
+}
+
+/// Sets how much slower each thread copies than a single thread does, if the measurements are of
+/// several counts of threads including one.
+pub fn slowdowns(measurements: &mut [Measurement]) {
+    let varied = measurements
+        .iter()
+        .any(|measurement| measurement.threads != measurements[0].threads);
+    if !varied {
+        return;
+    }
+    let single: HashMap<Key<'_>, f64> = measurements
+        .iter()
+        .filter(|measurement| measurement.threads == 1)
+        .map(|measurement| (key(measurement), measurement.nanoseconds))
+        .collect();
+    let slowdowns: Vec<Option<f64>> = measurements
+        .iter()
+        .map(|measurement| {
+            single
+                .get(&key(measurement))
+                .map(|single| measurement.nanoseconds / single)
+        })
+        .collect();
+    for (measurement, slowdown) in measurements.iter_mut().zip(slowdowns) {
+        measurement.slowdown = slowdown;
+    }
+}
+
+/// Reports for each kernel, size, state of the caches and backing store the most bandwidth all the
+/// threads together reach, and the fewest threads reaching nearly as much, where the memory
+/// saturates.
+pub fn report(measurements: &[Measurement], out: &mut dyn Write) -> io::Result<()> {
+    let mut curves: Vec<(&Measurement, Vec<(usize, f64)>)> = Vec::new();
+    for measurement in measurements {
+        let point = (measurement.threads, measurement.bandwidth);
+        match curves
+            .iter_mut()
+            .find(|(first, _)| key(first) == key(measurement))
+        {
+            Some((_, curve)) => curve.push(point),
+            None => curves.push((measurement, vec![point])),
+        }
+    }
+    let width = curves
+        .iter()
+        .map(|(first, _)| first.label().len())
+        .max()
+        .unwrap_or(0);
+    for (first, mut curve) in curves {
+        curve.sort_by_key(|&(threads, _)| threads);
+        let best = curve
+            .iter()
+            .map(|&(_, bandwidth)| bandwidth)
+            .fold(0.0, f64::max);
+        let &(threads, bandwidth) = curve
+            .iter()
+            .find(|&&(_, bandwidth)| bandwidth >= SATURATED * best)
+            .unwrap();
+        let most = curve.last().unwrap().0;
+        let saturation = if curve.len() == 1 {
+            format!(
+                "{:.1} GB/s, measured with {} only",
+                bandwidth,
+                count(threads)
+            )
+        } else if threads < most {
+            format!(
+                "saturates at {}, {:.1} of {:.1} GB/s",
+                count(threads),
+                bandwidth,
+                best
+            )
+        } else {
+            format!("{:.1} GB/s, still rising at {}", bandwidth, count(most))
+        };
+        writeln!(
+            out,
+            "{:>9}  {:<width$}  {}",
+            size::format(first.size),
+            first.label(),
+            saturation,
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+fn count(threads: usize) -> String {
+    match threads {
+        1 => "1 thread".to_string(),
+        _ => format!("{} threads", threads),
+    }
+}
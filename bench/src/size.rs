@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+const UNITS: &[(&str, usize)] = &[("GiB", 1 << 30), ("MiB", 1 << 20), ("KiB", 1 << 10)];
+
+/// Parses a number of bytes with an optional binary unit: `4096`, `4k`, `4K`, `4KiB`, `2M` or `1G`.
+pub fn parse(text: &str) -> Result<usize, String> {
+    let text = text.trim();
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(digits);
+    let number: usize = number
+        .parse()
+        .map_err(|_| format!("`{}` is not a size", text))?;
+    let multiplier = match unit.trim() {
+        "" | "B" => 1,
+        "k" | "K" | "KiB" => 1 << 10,
+        "m" | "M" | "MiB" => 1 << 20,
+        "g" | "G" | "GiB" => 1 << 30,
+        _ => return Err(format!("`{}` has an unknown unit", text)),
+    };
+    match number.checked_mul(multiplier) {
+        Some(0) => Err("sizes must not be zero".to_string()),
+        Some(size) => Ok(size),
+        None => Err(format!("`{}` is too large", text)),
+    }
+}
+
+/// Formats a number of bytes in the largest binary unit that divides it.
+pub fn format(size: usize) -> String {
+    for &(unit, multiplier) in UNITS {
+        if size >= multiplier && size & (multiplier - 1) == 0 {
+            return format!("{} {}", size / multiplier, unit);
+        }
+    }
+    format!("{} B", size)
+}
+
+/// A size, or an inclusive range of sizes written `start..end` that doubles from `start` on.
+pub struct Sizes {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Sizes {
+    /// The sizes of the range, with `steps` evenly spaced sizes per doubling.
+    pub fn expand(&self, steps: usize) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        let mut octave = self.start;
+        while octave <= self.end {
+            sizes.extend(
+                (0..steps)
+                    .map(|step| octave + octave * step / steps)
+                    .filter(|&size| size <= self.end),
+            );
+            octave = match octave.checked_mul(2) {
+                Some(octave) => octave,
+                None => break,
+            };
+        }
+        sizes.dedup();
+        sizes
+    }
+}
+
+impl FromStr for Sizes {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Sizes, String> {
+        let (start, end) = match text.find("..") {
+            Some(i) => (parse(&text[..i])?, parse(&text[i + 2..])?),
+            None => {
+                let size = parse(text)?;
+                (size, size)
+            }
+        };
+        if start > end {
+            return Err(format!("`{}` is an empty range", text));
+        }
+        Ok(Sizes { start, end })
+    }
+}
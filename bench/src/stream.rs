@@ -0,0 +1,87 @@
+use crate::report::Measurement;
+use crate::size;
+use memcpy::registry;
+use std::io::{self, Write};
+
+/// The sizes `--stream` covers by default: within the first and second level caches, within the
+/// last level cache, and past it, where STREAM measures.
+pub const SIZES: &str = "16k,256k,4M,256M";
+
+/// The operations in the order of the report, with the copy kernels under `memcpy` right after the
+/// `copy` of STREAM.
+const OPERATIONS: [&str; 7] = ["read", "write", "copy", "memcpy", "scale", "add", "triad"];
+
+/// The operation of a kernel and how many of its arrays it reads and writes, as STREAM counts the
+/// bytes it moves. A copy kernel reads its source and writes its destination.
+fn operation(kernel: &str) -> (&'static str, usize) {
+    match registry::STREAMS
+        .iter()
+        .find(|stream| stream.name == kernel)
+    {
+        Some(stream) => {
+            let name = kernel.split('_').next().unwrap_or("");
+            let operation = OPERATIONS
+                .iter()
+                .find(|&&operation| operation == name)
+                .unwrap_or(&"stream");
+            (operation, stream.reads + stream.writes)
+        }
+        None => ("memcpy", 2),
+    }
+}
+
+/// Reports for each size, state of the caches, backing store and count of threads the most
+/// bandwidth each operation of STREAM reaches, and the copy kernels, counting the bytes each reads
+/// and writes, with the kernel reaching it.
+pub fn report(measurements: &[Measurement], out: &mut dyn Write) -> io::Result<()> {
+    // The measurements of the same size and configuration, in the order they were made, with the
+    // best of each operation.
+    let mut groups: Vec<Vec<(&str, &Measurement, f64)>> = Vec::new();
+    for measurement in measurements {
+        let (operation, arrays) = operation(&measurement.kernel);
+        let bandwidth = arrays as f64 * measurement.bandwidth;
+        let same = |first: &Measurement| {
+            first.size == measurement.size
+                && first.cache == measurement.cache
+                && first.backing == measurement.backing
+                && first.threads == measurement.threads
+        };
+        let group = match groups.iter_mut().position(|group| same(group[0].1)) {
+            Some(i) => &mut groups[i],
+            None => {
+                groups.push(Vec::new());
+                groups.last_mut().unwrap()
+            }
+        };
+        match group.iter_mut().find(|(other, ..)| *other == operation) {
+            Some(best) if best.2 < bandwidth => *best = (operation, measurement, bandwidth),
+            Some(_) => {}
+            None => group.push((operation, measurement, bandwidth)),
+        }
+    }
+
+    writeln!(
+        out,
+        "{:>9}  {:<9}  {:>11}  kernel",
+        "size", "operation", "bandwidth"
+    )?;
+    for mut group in groups {
+        group.sort_by_key(|(operation, ..)| OPERATIONS.iter().position(|other| other == operation));
+        for (operation, best, bandwidth) in group {
+            let threads = match best.threads {
+                1 => String::new(),
+                threads => format!(", {} threads", threads),
+            };
+            writeln!(
+                out,
+                "{:>9}  {:<9}  {:>6.1} GB/s  {}{}",
+                size::format(best.size),
+                operation,
+                bandwidth,
+                best.label(),
+                threads
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use crate::machine::Cache;
+use crate::report::Measurement;
+use crate::size;
+use std::io::{self, Write};
+
+/// The sizes `--sweep` covers by default.
+pub const SIZES: &str = "16..2G";
+
+/// The sizes per doubling `--sweep` measures by default.
+pub const STEPS: usize = 4;
+
+/// About how many bytes a sweep copies per measurement, so that the small sizes make enough copies
+/// for the timer and the large ones do not take forever.
+pub const BUDGET: usize = 64 << 20;
+
+/// A cliff is a drop below this fraction of the best bandwidth since the previous cliff.
+const DROP: f64 = 0.7;
+
+/// No cache is smaller than this, and below it the bandwidth is dominated by the start-up cost of
+/// the kernels, which is not worth reporting.
+const SMALLEST: usize = 4 << 10;
+
+/// Where the bandwidth of a kernel drops sharply, between the last size before the drop and the
+/// first size after it.
+pub struct Cliff {
+    pub before: usize,
+    pub after: usize,
+    /// The bandwidth after, as a fraction of the best bandwidth before.
+    pub ratio: f64,
+}
+
+/// Finds the cliffs in the bandwidth of a kernel, given by size in increasing order. The drop must
+/// hold for two sizes in a row, so that one noisy measurement does not make a cliff, and drops
+/// within a doubling of each other make a single cliff, since the bandwidth often falls over a few
+/// sizes.
+pub fn cliffs(curve: &[(usize, f64)]) -> Vec<Cliff> {
+    let mut cliffs: Vec<Cliff> = Vec::new();
+    let mut best = 0.0f64;
+    // The best bandwidth before the last cliff.
+    let mut top = 0.0f64;
+    for (i, &(size, bandwidth)) in curve.iter().enumerate() {
+        if size < SMALLEST {
+            continue;
+        }
+        let below = |&(_, bandwidth): &(usize, f64)| bandwidth < DROP * best;
+        if i > 0 && below(&curve[i]) && curve.get(i + 1).is_none_or(below) {
+            let before = curve[i - 1].0;
+            match cliffs.last_mut() {
+                Some(last) if before < 2 * last.after => {
+                    last.after = size;
+                    last.ratio = bandwidth / top;
+                }
+                _ => {
+                    top = best;
+                    cliffs.push(Cliff {
+                        before,
+                        after: size,
+                        ratio: bandwidth / best,
+                    });
+                }
+            }
+            best = bandwidth;
+        } else {
+            best = best.max(bandwidth);
+        }
+    }
+    cliffs
+}
+
+/// Reports the cliffs of each kernel next to the caches of the machine. A copy touches both the
+/// source and the destination, so it outgrows a cache at about half its size.
+pub fn report(
+    measurements: &[Measurement],
+    caches: &[Cache],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let caches: Vec<String> = caches
+        .iter()
+        .map(|cache| format!("{} {}", cache.name, size::format(cache.size)))
+        .collect();
+    if caches.is_empty() {
+        writeln!(out, "caches: unknown")?;
+    } else {
+        writeln!(out, "caches: {}", caches.join(", "))?;
+    }
+
+    // A curve per kernel, state of the caches and backing store.
+    let mut curves: Vec<String> = Vec::new();
+    for measurement in measurements {
+        let curve = measurement.label();
+        if !curves.contains(&curve) {
+            curves.push(curve);
+        }
+    }
+    let width = curves.iter().map(String::len).max().unwrap_or(0);
+    for label in curves {
+        let mut curve: Vec<(usize, f64)> = measurements
+            .iter()
+            .filter(|measurement| measurement.label() == label)
+            .map(|measurement| (measurement.size, measurement.bandwidth))
+            .collect();
+        curve.sort_by_key(|&(size, _)| size);
+        let cliffs: Vec<String> = cliffs(&curve)
+            .iter()
+            .map(|cliff| {
+                format!(
+                    "{} to {} ({:.0} %)",
+                    size::format(cliff.before),
+                    size::format(cliff.after),
+                    100.0 * (cliff.ratio - 1.0)
+                )
+            })
+            .collect();
+        writeln!(
+            out,
+            "{:<width$}  {}",
+            label,
+            if cliffs.is_empty() {
+                "no cliff".to_string()
+            } else {
+                cliffs.join(", ")
+            },
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cliffs;
+
+    const K: usize = 1 << 10;
+
+    #[test]
+    fn drop() {
+        let curve = [
+            (4 * K, 10.0),
+            (8 * K, 10.0),
+            (16 * K, 11.0),
+            (32 * K, 10.0),
+            (64 * K, 5.5),
+            (128 * K, 5.0),
+        ];
+        let cliffs = cliffs(&curve);
+        assert_eq!(cliffs.len(), 1);
+        assert_eq!((cliffs[0].before, cliffs[0].after), (32 * K, 64 * K));
+        assert_eq!(cliffs[0].ratio, 0.5);
+    }
+
+    #[test]
+    fn noise() {
+        let curve = [
+            (4 * K, 10.0),
+            (8 * K, 10.0),
+            (16 * K, 5.0),
+            (32 * K, 10.0),
+            (64 * K, 10.0),
+        ];
+        assert!(cliffs(&curve).is_empty());
+    }
+
+    #[test]
+    fn merge() {
+        let curve = [
+            (16 * K, 10.0),
+            (32 * K, 10.0),
+            (40 * K, 6.0),
+            (48 * K, 3.0),
+            (64 * K, 3.0),
+        ];
+        let cliffs = cliffs(&curve);
+        assert_eq!(cliffs.len(), 1);
+        assert_eq!((cliffs[0].before, cliffs[0].after), (32 * K, 48 * K));
+        assert_eq!(cliffs[0].ratio, 0.3);
+    }
+
+    #[test]
+    fn small() {
+        let curve = [(K, 10.0), (2 * K, 3.0), (4 * K, 3.0), (8 * K, 3.0)];
+        assert!(cliffs(&curve).is_empty());
+    }
+}
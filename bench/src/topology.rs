@@ -0,0 +1,173 @@
+use crate::report::Measurement;
+use crate::size;
+use std::fs;
+use std::io::{self, Write};
+
+/// The sizes `--pairs` covers by default: within the first and second level caches, which SMT
+/// siblings share, within the last level cache, which the processors of a CCX or a die share, and
+/// past it.
+pub const SIZES: &str = "16k,256k,4M,256M";
+
+/// Where a processor sits in the machine, as Linux reports it.
+struct Processor {
+    id: usize,
+    /// The processors sharing its core, which identifies the core.
+    core: String,
+    /// The processors sharing its last level cache, which identifies the cache, if Linux says.
+    cache: Option<String>,
+    package: String,
+}
+
+/// Two processors to pin a pair of threads to, and how they relate.
+pub struct Pair {
+    /// `siblings`, `shared_cache`, `separate_caches` or `separate_packages`.
+    pub name: &'static str,
+    pub processors: (usize, usize),
+}
+
+/// A name for how two processors relate, and whether another processor relates so to the first.
+type Relation<'a> = (&'static str, &'a dyn Fn(&Processor) -> bool);
+
+/// A pair of the first online processor with another of each relation the machine has: an SMT
+/// sibling on its core, another core sharing its last level cache, a core on the same package with
+/// a last level cache of its own, as on another CCX of a Zen 2, and a core on another package.
+pub fn pairs() -> Vec<Pair> {
+    let processors = processors();
+    let first = match processors.first() {
+        Some(first) => first,
+        None => return Vec::new(),
+    };
+    let relations: [Relation<'_>; 4] = [
+        ("siblings", &|other| other.core == first.core),
+        ("shared_cache", &|other| {
+            other.core != first.core && other.package == first.package && other.cache == first.cache
+        }),
+        ("separate_caches", &|other| {
+            other.package == first.package && other.cache != first.cache
+        }),
+        ("separate_packages", &|other| other.package != first.package),
+    ];
+    relations
+        .iter()
+        .filter_map(|(name, related)| {
+            processors[1..]
+                .iter()
+                .find(|other| related(other))
+                .map(|other| Pair {
+                    name,
+                    processors: (first.id, other.id),
+                })
+        })
+        .collect()
+}
+
+/// The online processors, or none if Linux does not say.
+fn processors() -> Vec<Processor> {
+    let online = fs::read_to_string("/sys/devices/system/cpu/online").unwrap_or_default();
+    list(&online)
+        .into_iter()
+        .filter_map(|id| {
+            let directory = format!("/sys/devices/system/cpu/cpu{}", id);
+            let read = |name: &str| {
+                fs::read_to_string(format!("{}/{}", directory, name))
+                    .ok()
+                    .map(|text| text.trim().to_string())
+            };
+            Some(Processor {
+                id,
+                core: read("topology/thread_siblings_list")?,
+                cache: last_level_cache(&directory),
+                package: read("topology/physical_package_id")?,
+            })
+        })
+        .collect()
+}
+
+/// The processors sharing the highest level of cache of a processor.
+fn last_level_cache(directory: &str) -> Option<String> {
+    let mut last: Option<(usize, String)> = None;
+    for index in 0.. {
+        let cache = format!("{}/cache/index{}", directory, index);
+        let read = |name: &str| fs::read_to_string(format!("{}/{}", cache, name));
+        let (level, shared) = match (read("level"), read("shared_cpu_list")) {
+            (Ok(level), Ok(shared)) => (level, shared),
+            _ => break,
+        };
+        let level = level.trim().parse().unwrap_or(0);
+        if last.as_ref().is_none_or(|(last, _)| level > *last) {
+            last = Some((level, shared.trim().to_string()));
+        }
+    }
+    last.map(|(_, shared)| shared)
+}
+
+/// Parses a list of processors as Linux writes it, such as `0-3,8-11`.
+fn list(text: &str) -> Vec<usize> {
+    let mut processors = Vec::new();
+    for range in text.trim().split(',').filter(|range| !range.is_empty()) {
+        let mut ends = range.splitn(2, '-').map(|end| end.parse::<usize>());
+        match (ends.next(), ends.next()) {
+            (Some(Ok(start)), Some(Ok(end))) => processors.extend(start..=end),
+            (Some(Ok(processor)), None) => processors.push(processor),
+            _ => {}
+        }
+    }
+    processors
+}
+
+/// Reports for each kernel, size, state of the caches and backing store how much slower each
+/// thread of each pair copies than a thread alone does.
+pub fn report(measurements: &[Measurement], out: &mut dyn Write) -> io::Result<()> {
+    let mut pairs: Vec<&str> = Vec::new();
+    let mut rows: Vec<(&Measurement, Vec<&Measurement>)> = Vec::new();
+    for measurement in measurements {
+        let pair = match &measurement.pair {
+            Some(pair) => pair.as_str(),
+            None => continue,
+        };
+        if !pairs.contains(&pair) {
+            pairs.push(pair);
+        }
+        let same = |first: &Measurement| {
+            first.kernel == measurement.kernel
+                && first.size == measurement.size
+                && first.cache == measurement.cache
+                && first.backing == measurement.backing
+        };
+        match rows.iter_mut().find(|(first, _)| same(first)) {
+            Some((_, row)) => row.push(measurement),
+            None => rows.push((measurement, vec![measurement])),
+        }
+    }
+    let width = rows
+        .iter()
+        .map(|(first, _)| first.label().len())
+        .max()
+        .unwrap_or(0)
+        .max("kernel".len());
+
+    write!(out, "{:>9}  {:<width$}", "size", "kernel", width = width)?;
+    for pair in &pairs {
+        write!(out, "  {:>17}", pair)?;
+    }
+    writeln!(out)?;
+    for (first, row) in rows {
+        write!(
+            out,
+            "{:>9}  {:<width$}",
+            size::format(first.size),
+            first.label(),
+            width = width
+        )?;
+        for pair in &pairs {
+            let slowdown = row
+                .iter()
+                .find(|measurement| measurement.pair.as_deref() == Some(pair))
+                .and_then(|measurement| measurement.slowdown)
+                .map_or_else(String::new, |slowdown| format!("{:.2}×", slowdown));
+            write!(out, "  {:>17}", slowdown)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
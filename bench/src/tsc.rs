@@ -0,0 +1,121 @@
+/// Times copies in core cycles with the time stamp counter, which ticks at a constant rate, often
+/// not that of the core.
+#[derive(Clone, Copy)]
+pub struct Clock {
+    /// The fewest ticks between reads of the counter with nothing in between.
+    pub overhead: u64,
+    /// The core cycles per tick.
+    pub ratio: f64,
+}
+
+/// How many times the overhead and the ratio are measured, keeping the least disturbed.
+const ROUNDS: usize = 1000;
+
+/// The dependent additions per loop of the calibration, as `.rept` repeats them, each of which
+/// takes a cycle of the core. They add registers rather than constants, which recent cores fold
+/// together before executing them.
+const ADDITIONS: u64 = 100;
+const LOOPS: u64 = 20_000;
+
+impl Clock {
+    /// Measures the overhead of reading the counter, and how fast the core runs compared to it by
+    /// timing a chain of additions, each waiting for the one before.
+    #[cfg(target_arch = "x86_64")]
+    pub fn calibrate() -> Result<Clock, String> {
+        use core::arch::asm;
+        use core::arch::x86_64::{__cpuid, __get_cpuid_max};
+
+        // Leaf 0x80000001 of CPUID has `rdtscp` in bit 27 of EDX.
+        #[allow(unused_unsafe)]
+        let rdtscp = unsafe {
+            __get_cpuid_max(0x8000_0000).0 >= 0x8000_0001 && __cpuid(0x8000_0001).edx & 1 << 27 != 0
+        };
+        if !rdtscp {
+            return Err("--cycles needs a processor with rdtscp".to_string());
+        }
+
+        let overhead = (0..ROUNDS)
+            .map(|_| {
+                let start = start();
+                stop() - start
+            })
+            .min()
+            .unwrap();
+        let ticks = (0..ROUNDS / 100)
+            .map(|_| {
+                let start = start();
+                unsafe {
+                    asm!(
+                        "2:",
+                        ".rept 100",
+                        "add {x}, {x}",
+                        ".endr",
+                        "dec {n}",
+                        "jnz 2b",
+                        x = inout(reg) 0u64 => _,
+                        n = inout(reg) LOOPS => _,
+                        options(nomem, nostack)
+                    )
+                };
+                stop() - start
+            })
+            .min()
+            .unwrap();
+        Ok(Clock {
+            overhead,
+            ratio: (ADDITIONS * LOOPS) as f64 / ticks.saturating_sub(overhead).max(1) as f64,
+        })
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    pub fn calibrate() -> Result<Clock, String> {
+        Err("--cycles needs an x86_64 processor".to_string())
+    }
+
+    /// The core cycles per call of `f` over `calls` calls, without the overhead of the reads.
+    pub fn cycles(&self, calls: usize, mut f: impl FnMut()) -> f64 {
+        let start = start();
+        for _ in 0..calls {
+            f();
+        }
+        let ticks = stop() - start;
+        ticks.saturating_sub(self.overhead) as f64 * self.ratio / calls as f64
+    }
+}
+
+/// Reads the counter once everything before has run, and before anything after starts.
+#[cfg(target_arch = "x86_64")]
+fn start() -> u64 {
+    use core::arch::x86_64::{_mm_lfence, _rdtsc};
+
+    unsafe {
+        _mm_lfence();
+        let ticks = _rdtsc();
+        _mm_lfence();
+        ticks
+    }
+}
+
+/// Reads the counter once everything before has run, which `rdtscp` waits for itself, and before
+/// anything after starts.
+#[cfg(target_arch = "x86_64")]
+fn stop() -> u64 {
+    use core::arch::x86_64::{__rdtscp, _mm_lfence};
+
+    unsafe {
+        let mut processor = 0;
+        let ticks = __rdtscp(&mut processor);
+        _mm_lfence();
+        ticks
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn start() -> u64 {
+    unreachable!("there is no time stamp counter")
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn stop() -> u64 {
+    unreachable!("there is no time stamp counter")
+}
@@ -0,0 +1,643 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+
+fn bench(arguments: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_memcpy-bench"))
+        .args(arguments)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn json() {
+    let output = bench(&[
+        "--kernels",
+        "rep_*,mov_64",
+        "--sizes",
+        "4k..16k,100",
+        "--iterations",
+        "10",
+        "--threads",
+        "2",
+        "--baseline",
+        "rep_movsb",
+        "--format",
+        "json",
+    ]);
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(results["baseline"], "rep_movsb");
+    assert!(results["machine"]["name"].is_string());
+    let measurements = results["measurements"].as_array().unwrap();
+
+    // mov_64 and rep_movsq do not accept 100 bytes.
+    let names: Vec<(&str, u64)> = measurements
+        .iter()
+        .map(|m| (m["kernel"].as_str().unwrap(), m["size"].as_u64().unwrap()))
+        .collect();
+    let mut expected = Vec::new();
+    for &size in &[4096, 8192, 16384] {
+        expected.extend(vec![
+            ("mov_64", size),
+            ("rep_movsb", size),
+            ("rep_movsq", size),
+        ]);
+    }
+    expected.push(("rep_movsb", 100));
+    assert_eq!(names, expected);
+
+    for measurement in measurements {
+        assert_eq!(measurement["threads"], 2);
+        assert_eq!(measurement["cache"], "warm");
+        assert!(measurement["nanoseconds"].as_f64().unwrap() > 0.0);
+        assert!(measurement["bandwidth"].as_f64().unwrap() > 0.0);
+        if measurement["kernel"] == "rep_movsb" {
+            assert_eq!(measurement["relative"], 100.0);
+        }
+    }
+}
+
+#[test]
+fn csv() {
+    let output = bench(&[
+        "--kernels",
+        "loop_64",
+        "--sizes",
+        "1M",
+        "--iterations",
+        "2",
+        "--cold",
+        "--format",
+        "csv",
+    ]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines[0],
+        "kernel,size,source_offset,destination_offset,threads,cache,iterations,nanoseconds,\
+         bandwidth,relative"
+    );
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("loop_64,1048576,0,0,1,cold,2,"));
+}
+
+#[test]
+fn warm_and_cold() {
+    let output = bench(&[
+        "--kernels",
+        "rep_movsb,mov_64",
+        "--sizes",
+        "4k,64k",
+        "--iterations",
+        "10",
+        "--warm",
+        "--cold",
+    ]);
+    let lines: Vec<&str> = output.lines().collect();
+    let header: Vec<&str> = lines[0].split_whitespace().collect();
+    assert_eq!(
+        header,
+        [
+            "size", "kernel", "warm", "ns/copy", "GB/s", "baseline", "cold", "ns/copy", "GB/s",
+            "baseline"
+        ]
+    );
+    // A row per size and kernel, with both measurements relative to the baseline in the same
+    // state.
+    let rows: Vec<Vec<&str>> = lines[1..]
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows.len(), 4);
+    for (row, (size, kernel)) in rows.iter().zip(&[
+        ("4", "mov_64"),
+        ("4", "rep_movsb"),
+        ("64", "mov_64"),
+        ("64", "rep_movsb"),
+    ]) {
+        assert_eq!((row[0], row[2]), (*size, *kernel));
+        if *kernel == "rep_movsb" {
+            assert_eq!((row[5], row[9]), ("100", "100"), "{:?}", row);
+        }
+    }
+}
+
+#[test]
+fn errors() {
+    for arguments in &[
+        &["--kernels", "nothing"][..],
+        &["--baseline", "nothing"],
+        &["--sizes", "0"],
+        &["--sizes", "4k..1k"],
+        &["--sizes", "4X"],
+        &["--format", "xml"],
+        &["--sweep", "--iterations", "10"],
+        &["--sweep", "--offsets"],
+        &["--steps", "0"],
+        &["--threads", "0"],
+        &["--threads", "2..1"],
+        &["--saturation", "--sweep"],
+        &["--pairs", "--threads", "2"],
+        &["--backing", "nvme"],
+        &["--stream", "--offsets"],
+        &["--latency", "--offsets"],
+        &["--input", "/nonexistent.json"],
+        &["--input", "/nonexistent.json", "--iterations", "10"],
+        &["--update", "/nonexistent.md"],
+    ] {
+        let status = Command::new(env!("CARGO_BIN_EXE_memcpy-bench"))
+            .args(*arguments)
+            .output()
+            .unwrap()
+            .status;
+        assert!(!status.success(), "{:?} succeeds", arguments);
+    }
+}
+
+#[test]
+fn sweep() {
+    let output = Command::new(env!("CARGO_BIN_EXE_memcpy-bench"))
+        .args([
+            "--sweep",
+            "--kernels",
+            "rep_movsb",
+            "--sizes",
+            "4k..64k",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let measurements = &results["measurements"];
+    let sizes: Vec<u64> = measurements
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["size"].as_u64().unwrap())
+        .collect();
+    let mut expected = Vec::new();
+    for &octave in &[4096, 8192, 16384, 32768] {
+        expected.extend((0..4).map(|step| octave + octave * step / 4));
+    }
+    expected.push(65536);
+    assert_eq!(sizes, expected);
+    assert_eq!(measurements[0]["iterations"], (64 << 20) / 4096);
+
+    // The cliffs go to the standard error, away from the JSON.
+    let report = String::from_utf8(output.stderr).unwrap();
+    assert!(report.starts_with("caches: "), "{}", report);
+    assert!(report.lines().any(|line| line.starts_with("rep_movsb ")));
+}
+
+#[test]
+fn svg() {
+    let json = bench(&[
+        "--kernels",
+        "rep_movsb,mov_64",
+        "--sizes",
+        "4k..64k",
+        "--iterations",
+        "10",
+        "--format",
+        "json",
+    ]);
+    let path = env::temp_dir().join(format!("memcpy-bench-{}.json", std::process::id()));
+    fs::write(&path, json).unwrap();
+    let path = path.to_str().unwrap();
+
+    // The selection applies to the measurements read back.
+    let csv = bench(&["--input", path, "--kernels", "rep_*", "--format", "csv"]);
+    let svg = bench(&["--input", path, "--format", "svg"]);
+    fs::remove_file(path).unwrap();
+    assert_eq!(csv.lines().count(), 1 + 5);
+    assert!(csv
+        .lines()
+        .skip(1)
+        .all(|line| line.starts_with("rep_movsb,")));
+
+    assert!(svg.starts_with("<svg "), "{}", svg);
+    assert!(svg.ends_with("</svg>\n"));
+    // A panel per family, with a line per kernel.
+    assert!(svg.contains(">mov kernels</text>"));
+    assert!(svg.contains(">rep kernels</text>"));
+    assert_eq!(svg.matches("<polyline ").count(), 2);
+    assert_eq!(svg.matches("<circle ").count(), 2 * 5);
+}
+
+#[test]
+fn markdown() {
+    let directory = env::temp_dir().join(format!("memcpy-bench-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = |name: &str| directory.join(name).to_str().unwrap().to_string();
+    // The tables leave out the measurements of two threads of the third machine, and refuse the
+    // fourth, which has nothing else.
+    for &(machine, kernels, threads) in &[
+        ("First", "mov_32,rep_movsb", "1"),
+        ("Second", "mov_32", "1"),
+        ("Third", "mov_32", "1..2"),
+        ("Fourth", "mov_32", "2"),
+    ] {
+        let json = bench(&[
+            "--kernels",
+            kernels,
+            "--threads",
+            threads,
+            "--sizes",
+            "2k,4M",
+            "--iterations",
+            "10",
+            "--baseline",
+            "mov_32",
+            "--machine",
+            machine,
+            "--format",
+            "json",
+        ]);
+        fs::write(path(machine), json).unwrap();
+    }
+    fs::write(
+        path("README.md"),
+        "# Results\n\n<!-- results -->\nOld\n<!-- /results -->\n\nAfter\n",
+    )
+    .unwrap();
+    let arguments = [
+        "--input",
+        &path("First"),
+        &path("Second"),
+        &path("Third"),
+        "--format",
+        "markdown",
+    ];
+    let markdown = bench(&arguments);
+    let refused = Command::new(env!("CARGO_BIN_EXE_memcpy-bench"))
+        .args(["--input", &path("Fourth"), "--format", "markdown"])
+        .output()
+        .unwrap()
+        .status;
+    let updated = bench(&[&arguments[..], &["--update", &path("README.md")]].concat());
+    let readme = fs::read_to_string(path("README.md")).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    assert_eq!(lines[0], "#### 2 KiB");
+    assert_eq!(lines[2], "| kernel | First | Second | Third |");
+    assert_eq!(lines[3], "| :-- | --: | --: | --: |");
+    assert!(lines[4].starts_with("| `mov_32` | "), "{}", lines[4]);
+    assert_eq!(lines[4].matches(" ns (100 %)").count(), 3, "{}", lines[4]);
+    // The second machine has no measurement of rep_movsb.
+    assert!(lines[5].starts_with("| `rep_movsb` | "), "{}", lines[5]);
+    assert!(lines[5].ends_with(" |  |  |"), "{}", lines[5]);
+    assert!(lines[6].is_empty(), "{}", lines[6]);
+    assert!(markdown.contains("#### 4 MiB\n"));
+    assert!(markdown.contains("\n- First: "));
+    assert!(markdown.contains("\n- Third: "));
+    assert!(markdown.contains("Baseline `mov_32`, warm caches.\n"));
+    assert!(!refused.success());
+
+    assert_eq!(updated, "");
+    assert_eq!(
+        readme,
+        format!(
+            "# Results\n\n<!-- results -->\n{}<!-- /results -->\n\nAfter\n",
+            markdown
+        )
+    );
+}
+
+#[test]
+fn offsets() {
+    let arguments = [
+        "--offsets",
+        "--kernels",
+        "rep_movsb,mov_128",
+        "--iterations",
+        "2",
+        "--format",
+    ];
+    let csv = bench(&[&arguments[..], &["csv"]].concat());
+    let svg = bench(&[&arguments[..], &["svg"]].concat());
+
+    // mov_128 needs aligned buffers.
+    let offsets: Vec<usize> = (0..64).chain(vec![4032, 4064, 4080, 4088, 4095]).collect();
+    let rows: Vec<Vec<&str>> = csv
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').collect())
+        .collect();
+    assert_eq!(rows.len(), offsets.len() * offsets.len());
+    for (row, (source, destination)) in rows.iter().zip(
+        offsets
+            .iter()
+            .flat_map(|source| offsets.iter().map(move |destination| (source, destination))),
+    ) {
+        assert_eq!(row[0], "rep_movsb");
+        assert_eq!(row[1], "4096");
+        assert_eq!(row[2], source.to_string());
+        assert_eq!(row[3], destination.to_string());
+    }
+
+    assert!(svg.contains(">rep_movsb, 4 KiB</text>"), "{}", svg);
+    assert_eq!(svg.matches("<title>").count(), rows.len());
+    assert!(svg.contains("<title>source +4095, destination +3: "));
+}
+
+#[test]
+fn counters() {
+    // The software events can be counted everywhere, unlike the hardware ones in most virtual
+    // machines.
+    let arguments = [
+        "--counters",
+        "--kernels",
+        "rep_movsb",
+        "--sizes",
+        "4k",
+        "--iterations",
+        "10",
+    ];
+    let output = bench(&[&arguments[..], &["--format", "json"]].concat());
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let counters = &results["measurements"][0]["counters"];
+    assert!(
+        counters["page_faults"].as_f64().unwrap() >= 0.0,
+        "{}",
+        output
+    );
+
+    let output = bench(&[&arguments[..], &["--format", "csv"]].concat());
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].ends_with(",page_faults"), "{}", output);
+    assert_eq!(
+        lines[0].split(',').count(),
+        lines[1].split(',').count(),
+        "{}",
+        output
+    );
+
+    let output = bench(&arguments);
+    assert!(
+        output.lines().next().unwrap().ends_with("faults"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn cycles() {
+    let output = bench(&[
+        "--cycles",
+        "--kernels",
+        "rep_movsb",
+        "--sizes",
+        "64,2k",
+        "--iterations",
+        "1000",
+        "--format",
+        "json",
+    ]);
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let measurements = results["measurements"].as_array().unwrap();
+    assert_eq!(measurements.len(), 2);
+    for measurement in measurements {
+        let cycles = measurement["cycles"].as_f64().unwrap();
+        let bytes_per_cycle = measurement["bytes_per_cycle"].as_f64().unwrap();
+        assert!(cycles > 0.0, "{}", output);
+        let size = measurement["size"].as_f64().unwrap();
+        assert!((bytes_per_cycle * cycles - size).abs() < 1e-6 * size);
+    }
+
+    // Without --cycles, there are no cycles.
+    let output = bench(&["--kernels", "rep_movsb", "--sizes", "64", "--format", "csv"]);
+    assert!(output.lines().next().unwrap().ends_with(",relative"));
+}
+
+#[test]
+fn saturation() {
+    let arguments = [
+        "--saturation",
+        "--threads",
+        "1..2",
+        "--kernels",
+        "rep_movsb,mov_256_nt",
+        "--sizes",
+        "64k",
+        "--iterations",
+        "10",
+    ];
+    let output = bench(&[&arguments[..], &["--format", "json"]].concat());
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let measurements = results["measurements"].as_array().unwrap();
+    let threads: Vec<(&str, u64)> = measurements
+        .iter()
+        .map(|m| {
+            (
+                m["kernel"].as_str().unwrap(),
+                m["threads"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        threads,
+        vec![
+            ("mov_256_nt", 1),
+            ("rep_movsb", 1),
+            ("mov_256_nt", 2),
+            ("rep_movsb", 2),
+        ]
+    );
+    for measurement in measurements {
+        let slowdown = measurement["slowdown"].as_f64().unwrap();
+        if measurement["threads"] == 1 {
+            assert_eq!(slowdown, 1.0);
+        } else {
+            assert!(slowdown > 0.0);
+        }
+    }
+
+    let output = bench(&arguments);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].contains("threads"), "{}", output);
+    assert!(lines[0].ends_with("slowdown"), "{}", output);
+    let summary = &lines[lines.len() - 2..];
+    for (line, kernel) in summary.iter().zip(&["mov_256_nt", "rep_movsb"]) {
+        assert!(line.contains(kernel), "{}", output);
+        assert!(
+            line.contains("saturates at") || line.contains("still rising at 2 threads"),
+            "{}",
+            output
+        );
+    }
+
+    // A single count of threads has no slowdowns.
+    let output = bench(&[
+        "--kernels",
+        "rep_movsb",
+        "--threads",
+        "2",
+        "--format",
+        "csv",
+    ]);
+    assert!(output.lines().next().unwrap().ends_with(",relative"));
+}
+
+#[test]
+fn pairs() {
+    let output = Command::new(env!("CARGO_BIN_EXE_memcpy-bench"))
+        .args([
+            "--pairs",
+            "--kernels",
+            "rep_movsb",
+            "--sizes",
+            "64k",
+            "--iterations",
+            "10",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // A machine with a single processor has no pairs to pin threads to.
+    if !output.status.success() {
+        assert!(stderr.contains("no two processors"), "{}", stderr);
+        return;
+    }
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let measurements = results["measurements"].as_array().unwrap();
+    assert_eq!(measurements[0]["threads"], 1);
+    assert!(measurements[0].get("pair").is_none());
+    assert!(measurements.len() > 1);
+    for measurement in &measurements[1..] {
+        assert_eq!(measurement["threads"], 2);
+        let pair = measurement["pair"].as_str().unwrap();
+        assert!(stderr.contains(&format!("pinning {} to processors", pair)));
+        assert!(measurement["slowdown"].as_f64().unwrap() > 0.0);
+    }
+}
+
+#[test]
+fn bswap() {
+    let arguments = [
+        "--kernels",
+        "mov_32,bswap*",
+        "--sizes",
+        "4k",
+        "--iterations",
+        "10",
+    ];
+    let kernels = |output: &str| -> Vec<String> {
+        output
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().nth(2).unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(kernels(&bench(&arguments)), vec!["mov_32"]);
+    let swaps = kernels(&bench(&[&arguments[..], &["--bswap"]].concat()));
+    assert_eq!(swaps[0], "mov_32");
+    assert!(swaps.len() > 1, "{:?}", swaps);
+    assert!(swaps[1..].iter().all(|kernel| kernel.starts_with("bswap")));
+}
+
+#[test]
+fn backing() {
+    let arguments = [
+        "--backing",
+        "anonymous,memfd,tmpfs,file",
+        "--kernels",
+        "rep_movsb",
+        "--sizes",
+        "4k",
+        "--iterations",
+        "10",
+    ];
+    let output = bench(&[&arguments[..], &["--format", "json"]].concat());
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let backings: Vec<&str> = results["measurements"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["backing"].as_str().unwrap())
+        .collect();
+    assert_eq!(backings, vec!["anonymous", "memfd", "tmpfs", "file"]);
+
+    let output = bench(&[&arguments[..], &["--format", "csv"]].concat());
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].ends_with(",backing"), "{}", output);
+    assert!(lines[2].ends_with(",memfd"), "{}", output);
+
+    // The files are gone with the mappings.
+    let left = fs::read_dir(".")
+        .unwrap()
+        .filter(|entry| {
+            let name = entry.as_ref().unwrap().file_name();
+            name.to_string_lossy().starts_with(".memcpy-bench-")
+        })
+        .count();
+    assert_eq!(left, 0);
+}
+
+#[test]
+fn stream() {
+    let output = bench(&[
+        "--stream",
+        "--kernels",
+        "mov_64,read_64,write_64,scale_64,add_64,triad_64",
+        "--sizes",
+        "4k",
+        "--iterations",
+        "10",
+    ]);
+    let report = output.split("\n\n").nth(1).unwrap();
+    let operations: Vec<&str> = report
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().nth(2).unwrap())
+        .collect();
+    assert_eq!(
+        operations,
+        vec!["read", "write", "memcpy", "scale", "add", "triad"],
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn latency() {
+    let arguments = [
+        "--latency",
+        "--kernels",
+        "rep_movsb",
+        "--sizes",
+        "64,4k,64k",
+        "--iterations",
+        "10",
+    ];
+    let output = bench(&[&arguments[..], &["--format", "json"]].concat());
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let latencies = results["latencies"].as_array().unwrap();
+    // 64 bytes is too small for a cycle of pointers.
+    let sizes: Vec<u64> = latencies
+        .iter()
+        .map(|latency| latency["size"].as_u64().unwrap())
+        .collect();
+    assert_eq!(sizes, vec![4096, 65536]);
+    for latency in latencies {
+        assert!(latency["nanoseconds"].as_f64().unwrap() > 0.0);
+    }
+
+    let output = bench(&arguments);
+    let report = output.split("\n\n").last().unwrap();
+    assert!(report.starts_with("     size"), "{}", output);
+    assert_eq!(report.lines().count(), 3, "{}", output);
+    assert!(report
+        .lines()
+        .all(|line| line.ends_with("kernel") || line.ends_with("rep_movsb")));
+}
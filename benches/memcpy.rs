@@ -1,98 +1,161 @@
 use bytesize::ByteSize;
 use criterion::{criterion_group, criterion_main, Criterion};
+use memcpy::cache;
 use memmap::MmapMut;
-use std::time::Duration;
-
-/// Zen 2 (AMD)
-///     Desktop
-///     DDR4
-///     L1: 1 MiB
-///     L2: 8 MiB
-///     L3: 64 MiB
-///
-/// Coffee Lake (Intel)
-///     Mobile
-///     DDR4
-///     L1: 64 kiB
-///     L2: 256 kiB
-///     L3: 16 MiB
-///
-///                                 2 kiB
-///
-///                     Zen 2               Coffee Lake
-///
-/// mov 32         237 ns (100 %)          120 ns (100 %)
-///
-/// mov 64          81 ns ( 34 %)           89 ns ( 74 %)
-/// mov 128         64 ns ( 27 %)           37 ns ( 31 %)
-/// mov 256         17 ns (  7 %)           20 ns ( 17 %)
-///
-/// mov 64 (pl)     61 ns ( 26 %)           58 ns ( 48 %)
-/// mov 128 (pl)    30 ns ( 13 %)           30 ns ( 25 %)
-/// mov 256 (pl)    16 ns (  7 %)           16 ns ( 25 %)
-///
-/// mov 64 (nt)    111 ns ( 47 %)           81 ns ( 68 %)
-/// mov 128 (nt)   111 ns ( 47 %)           64 ns ( 53 %)
-/// mov 256 (nt)   111 ns ( 47 %)           54 ns ( 45 %)
-///
-/// mov 64 (p+n)   111 ns ( 47 %)           81 ns ( 49 %)
-/// mov 128 (p+n)  111 ns ( 47 %)           54 ns ( 45 %)
-/// mov 256 (p+n)  111 ns ( 47 %)           54 ns ( 45 %)
-///
-/// rep movsb       29 ns ( 12 %)           22 ns ( 18 %)
-/// rep movsq       29 ns ( 12 %)           23 ns ( 19 %)
-///
-///                                 4 MiB
-///
-///                     Zen 2               Coffee Lake
-///
-/// mov 32         476 μs (100 %)          306 μs (100 %)
-///
-/// mov 64         170 μs ( 36 %)          245 μs ( 80 %)
-/// mov 128        120 μs ( 25 %)          146 μs ( 48 %)
-/// mov 256         77 μs ( 16 %)          136 μs ( 44 %)
-///
-/// mov 64 (p)     123 μs ( 26 %)          177 μs ( 58 %)
-/// mov 128 (p)     81 μs ( 17 %)          146 µs ( 48 %)
-/// mov 256 (p)     80 μs ( 17 %)          134 µs ( 44 %)
-///
-/// mov 64 (n)     222 μs ( 47 %)          173 μs ( 57 %)
-/// mov 128 (n)    222 μs ( 47 %)          173 μs ( 57 %)
-/// mov 256 (n)    222 μs ( 47 %)          168 μs ( 55 %)
-///
-/// mov 64 (p+n)   222 μs ( 47 %)          197 ns ( 64 %)
-/// mov 128 (p+n)  222 μs ( 47 %)          178 ns ( 58 %)
-/// mov 256 (p+n)  222 μs ( 47 %)          179 ns ( 58 %)
-///
-/// rep movsb      310 μs ( 65 %)          167 μs ( 55 %)
-/// rep movsq      310 μs ( 65 %)          211 μs ( 68 %)
-///
-///                                 1 GiB
-///
-///                     Zen 2               Coffee Lake
-///
-/// mov 32         229 ms (100 %)          145 ms (100 %)
-///
-/// mov 64         191 ms (115 %)          121 ms ( 83 %)
-/// mov 128        158 ms ( 96 %)          106 ms ( 73 %)
-/// mov 256        142 ms ( 86 %)          112 ms ( 77 %)
-///
-/// mov 64 (p)     189 ms ( 83 %)          115 ms ( 79 %)
-/// mov 128 (p)    157 ms ( 69 %)          108 ms ( 74 %)
-/// mov 256 (p)    149 ms ( 65 %)          111 ms ( 77 %)
-///
-/// mov 64 (n)      89 ms ( 39 %)           87 ms ( 60 %)
-/// mov 128 (n)     86 ms ( 38 %)           80 ms ( 55 %)
-/// mov 256 (n)     82 ms ( 36 %)           98 ms ( 68 %)
+use std::cmp;
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::ptr;
+use std::slice;
+use std::time::{Duration, Instant};
+
+fn memfd(name: &str, size: usize) -> File {
+    let name = CString::new(name).unwrap();
+    let fd = unsafe { libc::memfd_create(name.as_ptr(), 0) };
+    assert!(fd >= 0, "memfd_create: {}", io::Error::last_os_error());
+    let mut file = unsafe { File::from_raw_fd(fd) };
+
+    let chunk = vec![1; cmp::min(size, 1 << 20)];
+    let mut written = 0;
+    while written < size {
+        let length = cmp::min(chunk.len(), size - written);
+        file.write_all(&chunk[..length]).unwrap();
+        written += length;
+    }
+    file
+}
+
+fn pipe() -> (File, File) {
+    let mut fds = [0; 2];
+    assert!(
+        unsafe { libc::pipe(fds.as_mut_ptr()) } == 0,
+        "pipe: {}",
+        io::Error::last_os_error()
+    );
+    // A larger pipe means fewer round trips through `splice`. The default of 64 kiB is kept if the
+    // limit in `/proc/sys/fs/pipe-max-size` is lower.
+    unsafe { libc::fcntl(fds[1], libc::F_SETPIPE_SZ, 1 << 20) };
+    unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+}
+
+fn copy_file_range(size: usize, source: &File, destination: &File) {
+    let mut source_offset: libc::loff_t = 0;
+    let mut destination_offset: libc::loff_t = 0;
+    while (source_offset as usize) < size {
+        let copied = unsafe {
+            libc::copy_file_range(
+                source.as_raw_fd(),
+                &mut source_offset,
+                destination.as_raw_fd(),
+                &mut destination_offset,
+                size - source_offset as usize,
+                0,
+            )
+        };
+        assert!(
+            copied > 0,
+            "copy_file_range: {}",
+            io::Error::last_os_error()
+        );
+    }
+}
+
+fn sendfile(size: usize, source: &File, mut destination: &File) {
+    // Unlike the source, the destination of `sendfile` is written at its file offset.
+    destination.seek(SeekFrom::Start(0)).unwrap();
+    let mut source_offset: libc::off_t = 0;
+    while (source_offset as usize) < size {
+        let copied = unsafe {
+            libc::sendfile(
+                destination.as_raw_fd(),
+                source.as_raw_fd(),
+                &mut source_offset,
+                size - source_offset as usize,
+            )
+        };
+        assert!(copied > 0, "sendfile: {}", io::Error::last_os_error());
+    }
+}
+
+fn splice(size: usize, source: &File, destination: &File, pipe: &(File, File)) {
+    let splice = |from: RawFd,
+                  from_offset: *mut libc::loff_t,
+                  to: RawFd,
+                  to_offset: *mut libc::loff_t,
+                  length| {
+        let spliced = unsafe {
+            libc::splice(
+                from,
+                from_offset,
+                to,
+                to_offset,
+                length,
+                libc::SPLICE_F_MOVE,
+            )
+        };
+        assert!(spliced > 0, "splice: {}", io::Error::last_os_error());
+        spliced as usize
+    };
+
+    let mut source_offset: libc::loff_t = 0;
+    let mut destination_offset: libc::loff_t = 0;
+    while (source_offset as usize) < size {
+        let length = size - source_offset as usize;
+        let mut buffered = splice(
+            source.as_raw_fd(),
+            &mut source_offset,
+            pipe.1.as_raw_fd(),
+            ptr::null_mut(),
+            length,
+        );
+        while buffered > 0 {
+            buffered -= splice(
+                pipe.0.as_raw_fd(),
+                ptr::null_mut(),
+                destination.as_raw_fd(),
+                &mut destination_offset,
+                buffered,
+            );
+        }
+    }
+}
+
+fn memcpy_libc(size: usize, source: *mut u8, destination: *mut u8) {
+    unsafe {
+        libc::memcpy(
+            destination as *mut libc::c_void,
+            source as *const libc::c_void,
+            size,
+        )
+    };
+}
+
+fn memcpy_copy_nonoverlapping(size: usize, source: *mut u8, destination: *mut u8) {
+    unsafe { ptr::copy_nonoverlapping(source, destination, size) };
+}
+
+fn memcpy_copy_from_slice(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = unsafe { slice::from_raw_parts(source, size) };
+    let destination = unsafe { slice::from_raw_parts_mut(destination, size) };
+    destination.copy_from_slice(source);
+}
+
+/// Each `intrinsics` entry follows the `mov` kernel it rewrites with `core::arch` intrinsics, save
+/// the `pf` ones, which prefetch and have no `mov` counterpart, and the `simd` entries follow the
+/// kernels of their width with `core::simd` vectors. The `libc memcpy`, `copy_nonoverlapping`,
+/// `copy_from_slice` and `loop` entries are what code gets without this crate and are the
+/// baselines to beat.
 ///
-/// mov 64 (p+n)    88 ms ( 38 %)           86 ms ( 59 %)
-/// mov 128 (p+n)   84 ms ( 37 %)           81 ms ( 56 %)
-/// mov 256 (p+n)   82 ms ( 36 %)          102 ms ( 70 %)
+/// Below 1 GiB, which no cache holds, each entry is followed by the same copy with its buffers
+/// flushed from the caches before each iteration, named with `(cold)`.
 ///
-/// rep movsb      162 ms ( 71 %)          129 ms ( 89 %)
-/// rep movsq      162 ms ( 71 %)          128 ms ( 88 %)
+/// The tables of results in the README, at the same sizes and relative to `mov 32`, are generated
+/// by `memcpy-bench` from the files in `bench/results`.
 fn run_benchmark_memcpy(c: &mut Criterion) {
     let sizes = vec![ByteSize::kib(2), ByteSize::mib(4), ByteSize::gib(1)];
+    let flush = cache::flusher();
 
     for size in sizes {
         let raw_size = size.as_u64() as usize;
@@ -101,31 +164,116 @@ fn run_benchmark_memcpy(c: &mut Criterion) {
         let mut run_benchmark = |name: &str, memcpy: fn(usize, *mut u8, *mut u8)| {
             let mut source = MmapMut::map_anon(raw_size).unwrap();
             let mut destination = MmapMut::map_anon(raw_size).unwrap();
-            group.bench_function(name, |b| {
-                b.iter(|| memcpy(raw_size, source.as_mut_ptr(), destination.as_mut_ptr()))
-            });
+            let (source, destination) = (source.as_mut_ptr(), destination.as_mut_ptr());
+            group.bench_function(name, |b| b.iter(|| memcpy(raw_size, source, destination)));
+            if size < ByteSize::gib(1) {
+                group.bench_function(format!("{} (cold)", name), |b| {
+                    b.iter_custom(|iterations| {
+                        let mut elapsed = Duration::from_secs(0);
+                        for _ in 0..iterations {
+                            flush(source, raw_size);
+                            flush(destination, raw_size);
+                            let start = Instant::now();
+                            memcpy(raw_size, source, destination);
+                            elapsed += start.elapsed();
+                        }
+                        elapsed
+                    })
+                });
+            }
         };
 
+        run_benchmark("libc memcpy", memcpy_libc);
+        run_benchmark("copy_nonoverlapping", memcpy_copy_nonoverlapping);
+        run_benchmark("copy_from_slice", memcpy_copy_from_slice);
+        run_benchmark("loop 64", memcpy::memcpy_loop_64);
+        #[cfg(target_feature = "avx")]
+        run_benchmark("loop 256", memcpy::memcpy_loop_256);
         run_benchmark("mov 32", memcpy::memcpy_mov_32);
+        run_benchmark("intrinsics 32", memcpy::memcpy_intrinsics_32);
         run_benchmark("mov 64", memcpy::memcpy_mov_64);
+        run_benchmark("intrinsics 64", memcpy::memcpy_intrinsics_64);
         run_benchmark("mov 128", memcpy::memcpy_mov_128);
+        run_benchmark("intrinsics 128", memcpy::memcpy_intrinsics_128);
+        run_benchmark("simd 128", memcpy::memcpy_simd_128);
         #[cfg(target_feature = "avx")]
         run_benchmark("mov 256", memcpy::memcpy_mov_256);
+        #[cfg(target_feature = "avx")]
+        run_benchmark("intrinsics 256", memcpy::memcpy_intrinsics_256);
+        run_benchmark("simd 256", memcpy::memcpy_simd_256);
+        run_benchmark("simd 512", memcpy::memcpy_simd_512);
         run_benchmark("mov 64 (pl)", memcpy::memcpy_mov_64_pl);
+        run_benchmark("intrinsics 64 (pl)", memcpy::memcpy_intrinsics_64_pl);
         run_benchmark("mov 128 (pl)", memcpy::memcpy_mov_128_pl);
+        run_benchmark("intrinsics 128 (pl)", memcpy::memcpy_intrinsics_128_pl);
+        run_benchmark("simd 128 (pl)", memcpy::memcpy_simd_128_pl);
         #[cfg(target_feature = "avx")]
         run_benchmark("mov 256 (pl)", memcpy::memcpy_mov_256_pl);
+        #[cfg(target_feature = "avx")]
+        run_benchmark("intrinsics 256 (pl)", memcpy::memcpy_intrinsics_256_pl);
+        run_benchmark("simd 256 (pl)", memcpy::memcpy_simd_256_pl);
+        run_benchmark("simd 512 (pl)", memcpy::memcpy_simd_512_pl);
         run_benchmark("mov 64 (nt)", memcpy::memcpy_mov_64_nt);
+        run_benchmark("intrinsics 64 (nt)", memcpy::memcpy_intrinsics_64_nt);
         run_benchmark("mov 128 (nt)", memcpy::memcpy_mov_128_nt);
+        run_benchmark("intrinsics 128 (nt)", memcpy::memcpy_intrinsics_128_nt);
         #[cfg(target_feature = "avx")]
         run_benchmark("mov 256 (nt)", memcpy::memcpy_mov_256_nt);
+        #[cfg(target_feature = "avx")]
+        run_benchmark("intrinsics 256 (nt)", memcpy::memcpy_intrinsics_256_nt);
         run_benchmark("mov 64 (nt+pl)", memcpy::memcpy_mov_64_nt_pl);
+        run_benchmark("intrinsics 64 (nt+pl)", memcpy::memcpy_intrinsics_64_nt_pl);
         run_benchmark("mov 128 (nt+pl)", memcpy::memcpy_mov_128_nt_pl);
+        run_benchmark(
+            "intrinsics 128 (nt+pl)",
+            memcpy::memcpy_intrinsics_128_nt_pl,
+        );
         #[cfg(target_feature = "avx")]
         run_benchmark("mov 256 (nt+pl)", memcpy::memcpy_mov_256_nt_pl);
+        #[cfg(target_feature = "avx")]
+        run_benchmark(
+            "intrinsics 256 (nt+pl)",
+            memcpy::memcpy_intrinsics_256_nt_pl,
+        );
+        run_benchmark("intrinsics 64 (pl+pf)", memcpy::memcpy_intrinsics_64_pl_pf);
+        run_benchmark(
+            "intrinsics 128 (pl+pf)",
+            memcpy::memcpy_intrinsics_128_pl_pf,
+        );
+        #[cfg(target_feature = "avx")]
+        run_benchmark(
+            "intrinsics 256 (pl+pf)",
+            memcpy::memcpy_intrinsics_256_pl_pf,
+        );
+        run_benchmark(
+            "intrinsics 64 (nt+pl+pf)",
+            memcpy::memcpy_intrinsics_64_nt_pl_pf,
+        );
+        run_benchmark(
+            "intrinsics 128 (nt+pl+pf)",
+            memcpy::memcpy_intrinsics_128_nt_pl_pf,
+        );
+        #[cfg(target_feature = "avx")]
+        run_benchmark(
+            "intrinsics 256 (nt+pl+pf)",
+            memcpy::memcpy_intrinsics_256_nt_pl_pf,
+        );
         run_benchmark("rep movsb", memcpy::memcpy_rep_movsb);
         run_benchmark("rep movsq", memcpy::memcpy_rep_movsq);
 
+        let mut run_benchmark_kernel = |name: &str, copy: &dyn Fn(usize, &File, &File)| {
+            let source = memfd("source", raw_size);
+            let destination = memfd("destination", raw_size);
+            group.bench_function(name, |b| b.iter(|| copy(raw_size, &source, &destination)));
+        };
+
+        run_benchmark_kernel("copy_file_range", &copy_file_range);
+        run_benchmark_kernel("sendfile", &sendfile);
+        let pipe = pipe();
+        run_benchmark_kernel("splice", &|size, source, destination| {
+            splice(size, source, destination, &pipe)
+        });
+
         group.finish()
     }
 }
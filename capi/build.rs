@@ -0,0 +1,41 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Set to copy the generated header over the one in `include`, which is otherwise left alone.
+const UPDATE: &str = "MEMCPY_CRATE_UPDATE_HEADER";
+
+fn main() {
+    // The configuration of cbindgen is in this script rather than in a `cbindgen.toml`.
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={}", UPDATE);
+
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("MEMCPY_CRATE_H".to_string()),
+        autogen_warning: Some(
+            "/* Generated by cbindgen from `src/lib.rs`. Do not edit. */".to_string(),
+        ),
+        no_includes: true,
+        sys_includes: vec!["stddef.h".to_string(), "stdint.h".to_string()],
+        usize_is_size_t: true,
+        ..Default::default()
+    };
+    let header = Path::new(&out_dir).join("memcpy_crate.h");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("cannot generate the C header")
+        .write_to_file(&header);
+    if env::var_os(UPDATE).is_some() {
+        fs::copy(
+            &header,
+            Path::new(&crate_dir).join("include/memcpy_crate.h"),
+        )
+        .expect("cannot update the C header");
+    }
+}
@@ -0,0 +1,72 @@
+#ifndef MEMCPY_CRATE_H
+#define MEMCPY_CRATE_H
+
+/* Generated by cbindgen from `src/lib.rs`. Do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Copies `size` bytes like `memcpy`, picking a kernel from the size and alignment.
+ */
+void *memcpy_crate_copy(void *destination, const void *source, size_t size);
+
+/**
+ * Copies `size` bytes like `memcpy`, with non-temporal stores whatever the size.
+ */
+void *memcpy_crate_copy_nt(void *destination, const void *source, size_t size);
+
+/**
+ * Copies `size` bytes like `memmove`, so the source and destination may overlap.
+ */
+void *memcpy_crate_move(void *destination, const void *source, size_t size);
+
+/**
+ * Sets `size` bytes to `value` like `memset`.
+ */
+void *memcpy_crate_set(void *destination, int value, size_t size);
+
+/**
+ * Returns the number of kernels in the registry.
+ */
+size_t memcpy_crate_kernel_count(void);
+
+/**
+ * Returns the index of the kernel called `name`, or -1 if there is none.
+ *
+ * # Safety
+ *
+ * `name` must be null or point to a nul-terminated string.
+ */
+ptrdiff_t memcpy_crate_kernel_find(const char *name);
+
+/**
+ * Writes the name of the kernel at `index` to `buffer` like `snprintf` and returns its length, or
+ * 0 if there is no such kernel.
+ *
+ * # Safety
+ *
+ * `buffer` must be null or point to `size` writable bytes.
+ */
+size_t memcpy_crate_kernel_name(size_t index, char *buffer, size_t size);
+
+/**
+ * Returns the alignment the kernel at `index` requires of the source and destination, or 0 if
+ * there is no such kernel.
+ */
+size_t memcpy_crate_kernel_alignment(size_t index);
+
+/**
+ * Returns the multiple of which sizes passed to the kernel at `index` must be, or 0 if there is no
+ * such kernel.
+ */
+size_t memcpy_crate_kernel_granularity(size_t index);
+
+/**
+ * Copies `size` bytes with the kernel at `index`. Returns 0 on success, or -1 without copying
+ * anything if there is no such kernel, the processor lacks its features or it does not accept the
+ * size or alignment.
+ */
+int memcpy_crate_kernel_copy(size_t index, void *destination, const void *source, size_t size);
+
+#endif /* MEMCPY_CRATE_H */
@@ -0,0 +1,138 @@
+use memcpy::{dispatch, registry};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+// The C API follows the libc argument order: destination, source, size. Kernels are named by their
+// index in the registry, which the `memcpy_crate_kernel_*` functions look up.
+
+/// Copies `size` bytes like `memcpy`, picking a kernel from the size and alignment.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_copy(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memcpy(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+/// Copies `size` bytes like `memcpy`, with non-temporal stores whatever the size.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_copy_nt(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memcpy_nt(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+/// Copies `size` bytes like `memmove`, so the source and destination may overlap.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_move(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memmove(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+/// Sets `size` bytes to `value` like `memset`.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_set(
+    destination: *mut c_void,
+    value: c_int,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memset(size, value as u8, destination as *mut u8);
+    destination
+}
+
+/// Returns the number of kernels in the registry.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_kernel_count() -> usize {
+    registry::KERNELS.len()
+}
+
+/// Returns the index of the kernel called `name`, or -1 if there is none.
+///
+/// # Safety
+///
+/// `name` must be null or point to a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn memcpy_crate_kernel_find(name: *const c_char) -> isize {
+    if name.is_null() {
+        return -1;
+    }
+    let name = CStr::from_ptr(name);
+    registry::KERNELS
+        .iter()
+        .position(|kernel| kernel.name.as_bytes() == name.to_bytes())
+        .map_or(-1, |index| index as isize)
+}
+
+/// Writes the name of the kernel at `index` to `buffer` like `snprintf` and returns its length, or
+/// 0 if there is no such kernel.
+///
+/// # Safety
+///
+/// `buffer` must be null or point to `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn memcpy_crate_kernel_name(
+    index: usize,
+    buffer: *mut c_char,
+    size: usize,
+) -> usize {
+    let name = match registry::KERNELS.get(index) {
+        Some(kernel) => kernel.name,
+        None => return 0,
+    };
+    if size > 0 && !buffer.is_null() {
+        let length = name.len().min(size - 1);
+        ptr::copy_nonoverlapping(name.as_ptr() as *const c_char, buffer, length);
+        *buffer.add(length) = 0;
+    }
+    name.len()
+}
+
+/// Returns the alignment the kernel at `index` requires of the source and destination, or 0 if
+/// there is no such kernel.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_kernel_alignment(index: usize) -> usize {
+    registry::KERNELS
+        .get(index)
+        .map_or(0, |kernel| kernel.alignment)
+}
+
+/// Returns the multiple of which sizes passed to the kernel at `index` must be, or 0 if there is no
+/// such kernel.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_kernel_granularity(index: usize) -> usize {
+    registry::KERNELS
+        .get(index)
+        .map_or(0, |kernel| kernel.granularity)
+}
+
+/// Copies `size` bytes with the kernel at `index`. Returns 0 on success, or -1 without copying
+/// anything if there is no such kernel, the processor lacks its features or it does not accept the
+/// size or alignment.
+#[no_mangle]
+pub extern "C" fn memcpy_crate_kernel_copy(
+    index: usize,
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> c_int {
+    match registry::KERNELS.get(index) {
+        Some(kernel)
+            if kernel.is_supported()
+                && kernel.accepts(size, source as *const u8, destination as *const u8) =>
+        {
+            (kernel.copy)(size, source as *mut u8, destination as *mut u8);
+            0
+        }
+        _ => -1,
+    }
+}
@@ -0,0 +1,114 @@
+#include <memcpy_crate.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define SIZE (1 << 20)
+
+static int failures = 0;
+
+#define CHECK(condition, ...)                                                                      \
+    do {                                                                                           \
+        if (!(condition)) {                                                                        \
+            fprintf(stderr, __VA_ARGS__);                                                          \
+            fprintf(stderr, "\n");                                                                 \
+            ++failures;                                                                            \
+        }                                                                                          \
+    } while (0)
+
+static unsigned char pattern(size_t i) {
+    return (unsigned char)(i * 7 + i / 251);
+}
+
+static void fill(unsigned char *buffer, size_t size) {
+    for (size_t i = 0; i < size; ++i) {
+        buffer[i] = pattern(i);
+    }
+}
+
+static int copied(const unsigned char *buffer, size_t size, size_t from) {
+    for (size_t i = 0; i < size; ++i) {
+        if (buffer[i] != pattern(from + i)) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(void) {
+    unsigned char *source = aligned_alloc(4096, SIZE);
+    unsigned char *destination = aligned_alloc(4096, SIZE);
+    fill(source, SIZE);
+
+    size_t count = memcpy_crate_kernel_count();
+    CHECK(count > 0, "the registry is empty");
+    for (size_t index = 0; index < count; ++index) {
+        char name[64];
+        size_t length = memcpy_crate_kernel_name(index, name, sizeof(name));
+        CHECK(length > 0 && length == strlen(name), "kernel %zu has no name", index);
+        CHECK(memcpy_crate_kernel_find(name) == (intptr_t)index, "cannot find %s", name);
+
+        size_t alignment = memcpy_crate_kernel_alignment(index);
+        size_t granularity = memcpy_crate_kernel_granularity(index);
+        CHECK(alignment > 0 && granularity > 0, "%s has no alignment or granularity", name);
+
+        size_t size = 64 * granularity;
+        memset(destination, 0, SIZE);
+        CHECK(memcpy_crate_kernel_copy(index, destination, source, size) == 0, "%s failed", name);
+        CHECK(copied(destination, size, 0) && destination[size] == 0, "%s copied wrong", name);
+        CHECK(memcpy_crate_kernel_copy(index, destination, source, 0) == -1,
+              "%s accepted an empty copy", name);
+        if (alignment > 1) {
+            CHECK(memcpy_crate_kernel_copy(index, destination + 1, source, size) == -1,
+                  "%s accepted a misaligned destination", name);
+        }
+        if (granularity > 1) {
+            CHECK(memcpy_crate_kernel_copy(index, destination, source, size + 1) == -1,
+                  "%s accepted a partial size", name);
+        }
+    }
+    CHECK(memcpy_crate_kernel_find("no such kernel") == -1, "found a kernel that does not exist");
+    CHECK(memcpy_crate_kernel_name(count, NULL, 0) == 0, "named a kernel that does not exist");
+    CHECK(memcpy_crate_kernel_copy(count, destination, source, 64) == -1,
+          "ran a kernel that does not exist");
+
+    char truncated[4];
+    size_t length = memcpy_crate_kernel_name(0, truncated, sizeof(truncated));
+    CHECK(length >= sizeof(truncated) && strlen(truncated) == sizeof(truncated) - 1,
+          "kernel names are not truncated like snprintf");
+
+    size_t sizes[] = {0, 1, 31, 4097, SIZE - 64};
+    for (size_t i = 0; i < sizeof(sizes) / sizeof(sizes[0]); ++i) {
+        size_t size = sizes[i];
+
+        memset(destination, 0, SIZE);
+        CHECK(memcpy_crate_copy(destination + 3, source + 3, size) == destination + 3 &&
+                  copied(destination + 3, size, 3),
+              "memcpy_crate_copy failed for %zu bytes", size);
+
+        memset(destination, 0, SIZE);
+        CHECK(memcpy_crate_copy_nt(destination + 32, source, size) == destination + 32 &&
+                  copied(destination + 32, size, 0),
+              "memcpy_crate_copy_nt failed for %zu bytes", size);
+
+        fill(destination, SIZE);
+        CHECK(memcpy_crate_move(destination + 5, destination, size) == destination + 5 &&
+                  copied(destination + 5, size, 0),
+              "memcpy_crate_move failed for %zu bytes", size);
+
+        memset(destination, 0, SIZE);
+        CHECK(memcpy_crate_set(destination + 1, 0xa5, size) == destination + 1 &&
+                  destination[0] == 0 && destination[size + 1] == 0,
+              "memcpy_crate_set failed for %zu bytes", size);
+        for (size_t j = 0; j < size; ++j) {
+            if (destination[j + 1] != 0xa5) {
+                CHECK(0, "memcpy_crate_set failed for %zu bytes", size);
+                break;
+            }
+        }
+    }
+
+    free(source);
+    free(destination);
+    return failures != 0;
+}
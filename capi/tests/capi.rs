@@ -0,0 +1,46 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+#[test]
+fn capi() {
+    // Integration tests run from `target/<profile>/deps`, where Cargo also puts the libraries.
+    let executable = env::current_exe().unwrap();
+    let library = executable.parent().unwrap().join("libmemcpy_crate.a");
+    assert!(library.exists(), "{} was not built", library.display());
+
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let header = fs::read_to_string(manifest_dir.join("include/memcpy_crate.h")).unwrap();
+    let generated = fs::read_to_string(Path::new(env!("OUT_DIR")).join("memcpy_crate.h")).unwrap();
+    assert!(
+        header == generated,
+        "include/memcpy_crate.h is out of date, build with MEMCPY_CRATE_UPDATE_HEADER set"
+    );
+
+    let source = manifest_dir.join("tests/capi.c");
+    let program = env::temp_dir().join(format!("memcpy-capi-{}", process::id()));
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg("-std=c11")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-o")
+        .arg(&program)
+        .arg(&source)
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm"])
+        .status()
+        .unwrap();
+    assert!(status.success(), "cannot compile {}", source.display());
+
+    let output = Command::new(&program).output().unwrap();
+    let _ = fs::remove_file(&program);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,62 @@
+// The library may be the `memcpy` the compiler calls, so it must not turn loops into calls to it.
+#![no_builtins]
+
+use memcpy::dispatch;
+use std::os::raw::{c_int, c_void};
+use std::process;
+
+// These follow the libc ABI, which takes the destination first and the size last, the reverse of
+// the kernels. Nothing here may call the libc functions these replace, or they would call
+// themselves.
+
+#[no_mangle]
+pub extern "C" fn memcpy(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memcpy(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+#[no_mangle]
+pub extern "C" fn memmove(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memmove(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+#[no_mangle]
+pub extern "C" fn memset(destination: *mut c_void, value: c_int, size: usize) -> *mut c_void {
+    dispatch::memset(size, value as u8, destination as *mut u8);
+    destination
+}
+
+#[no_mangle]
+pub extern "C" fn mempcpy(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    dispatch::memcpy(size, source as *mut u8, destination as *mut u8);
+    (destination as *mut u8).wrapping_add(size) as *mut c_void
+}
+
+#[no_mangle]
+pub extern "C" fn __memcpy_chk(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+    destination_size: usize,
+) -> *mut c_void {
+    // Glibc reports the overflow through `__chk_fail`, which formats a message and may copy it with
+    // the `memcpy` this replaces. `abort` is libc too, but it only raises `SIGABRT`, which is where
+    // `__chk_fail` ends anyway.
+    if size > destination_size {
+        process::abort();
+    }
+    memcpy(destination, source, size)
+}
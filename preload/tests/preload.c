@@ -0,0 +1,140 @@
+#define _GNU_SOURCE
+#include <dlfcn.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+void *__memcpy_chk(void *destination, const void *source, size_t size, size_t destination_size);
+
+/* Going through volatile pointers keeps the compiler from expanding the calls inline. */
+static void *(*volatile memcpy_)(void *, const void *, size_t) = memcpy;
+static void *(*volatile memmove_)(void *, const void *, size_t) = memmove;
+static void *(*volatile memset_)(void *, int, size_t) = memset;
+static void *(*volatile mempcpy_)(void *, const void *, size_t) = mempcpy;
+static void *(*volatile memcpy_chk_)(void *, const void *, size_t, size_t) = __memcpy_chk;
+
+static const size_t sizes[] = {
+    0, 1, 7, 31, 32, 255, 256, 511, 512, 513, 4095, 4096, 65539, 20 << 20,
+};
+static const size_t offsets[] = {0, 1, 16, 31};
+
+#define COUNT(array) (sizeof(array) / sizeof(array[0]))
+#define GUARD 64
+
+static unsigned char pattern(size_t i) {
+    return (unsigned char)(i * 7 + i / 251);
+}
+
+static void fail(const char *function, size_t size, size_t source_offset,
+                 size_t destination_offset) {
+    fprintf(stderr, "%s failed for size %zu, source offset %zu and destination offset %zu\n",
+            function, size, source_offset, destination_offset);
+    exit(1);
+}
+
+/* Checks that `buffer[start, start + size)` holds the pattern from `from` on, and the guard bytes
+ * around it are still zero. */
+static int check(const unsigned char *buffer, size_t window, size_t start, size_t size,
+                 size_t from) {
+    for (size_t i = 0; i < window; ++i) {
+        unsigned char expected = i >= start && i < start + size ? pattern(from + i - start) : 0;
+        if (buffer[i] != expected) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(void) {
+    Dl_info info;
+    if (!dladdr((void *)memcpy_, &info)) {
+        fprintf(stderr, "cannot find the library defining memcpy\n");
+        return 1;
+    }
+    printf("%s\n", info.dli_fname);
+
+    size_t largest = sizes[COUNT(sizes) - 1];
+    size_t length = largest + 3 * GUARD;
+    unsigned char *source = aligned_alloc(4096, length);
+    unsigned char *destination = aligned_alloc(4096, length);
+    for (size_t i = 0; i < length; ++i) {
+        source[i] = pattern(i);
+    }
+
+    for (size_t s = 0; s < COUNT(sizes); ++s) {
+        size_t size = sizes[s];
+        /* Only the copy and the guard bytes around it are cleared and checked. */
+        size_t window = size + 3 * GUARD;
+        for (size_t i = 0; i < COUNT(offsets); ++i) {
+            for (size_t j = 0; j < COUNT(offsets); ++j) {
+                size_t source_offset = offsets[i];
+                size_t destination_offset = GUARD + offsets[j];
+
+                for (size_t k = 0; k < window; ++k) {
+                    destination[k] = 0;
+                }
+                if (memcpy_(destination + destination_offset, source + source_offset, size) !=
+                        destination + destination_offset ||
+                    !check(destination, window, destination_offset, size, source_offset)) {
+                    fail("memcpy", size, source_offset, destination_offset);
+                }
+
+                for (size_t k = 0; k < window; ++k) {
+                    destination[k] = 0;
+                }
+                if (mempcpy_(destination + destination_offset, source + source_offset, size) !=
+                        destination + destination_offset + size ||
+                    !check(destination, window, destination_offset, size, source_offset)) {
+                    fail("mempcpy", size, source_offset, destination_offset);
+                }
+
+                for (size_t k = 0; k < window; ++k) {
+                    destination[k] = 0;
+                }
+                memcpy_chk_(destination + destination_offset, source + source_offset, size, size);
+                if (!check(destination, window, destination_offset, size, source_offset)) {
+                    fail("__memcpy_chk", size, source_offset, destination_offset);
+                }
+            }
+
+            /* Overlapping moves in both directions, within a buffer holding the pattern. */
+            size_t shift = offsets[i] + 1;
+            for (size_t k = 0; k < window; ++k) {
+                destination[k] = 0;
+            }
+            for (size_t k = 0; k < size; ++k) {
+                destination[GUARD + k] = pattern(k);
+            }
+            memmove_(destination + GUARD + shift, destination + GUARD, size);
+            for (size_t k = 0; k < shift; ++k) {
+                destination[GUARD + k] = 0;
+            }
+            if (!check(destination, window, GUARD + shift, size, 0)) {
+                fail("memmove up", size, 0, shift);
+            }
+
+            memmove_(destination + GUARD, destination + GUARD + shift, size);
+            for (size_t k = size; k < size + shift; ++k) {
+                destination[GUARD + k] = 0;
+            }
+            if (!check(destination, window, GUARD, size, 0)) {
+                fail("memmove down", size, shift, 0);
+            }
+
+            size_t offset = GUARD + offsets[i];
+            for (size_t k = 0; k < window; ++k) {
+                destination[k] = 0;
+            }
+            memset_(destination + offset, 0xa5, size);
+            for (size_t k = 0; k < window; ++k) {
+                if (destination[k] != (k >= offset && k < offset + size ? 0xa5 : 0)) {
+                    fail("memset", size, 0, offset);
+                }
+            }
+        }
+    }
+
+    free(source);
+    free(destination);
+    return 0;
+}
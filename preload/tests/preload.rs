@@ -0,0 +1,78 @@
+use std::env;
+use std::path::Path;
+use std::process::{self, Command};
+
+/// Runs `tests/preload.c` with `library` preloaded and checks that its `memcpy` is the one called.
+fn run(library: &Path, name: &str) {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/preload.c");
+    let program = env::temp_dir().join(format!("memcpy-preload-{}-{}", name, process::id()));
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    // Without optimisations, the compiler does not turn the loops of the test into calls to the
+    // functions under test.
+    let status = Command::new(compiler)
+        .arg("-O0")
+        .arg("-o")
+        .arg(&program)
+        .arg(&source)
+        .arg("-ldl")
+        .status()
+        .unwrap();
+    assert!(status.success(), "cannot compile {}", source.display());
+
+    let output = Command::new(&program)
+        .env("LD_PRELOAD", library)
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_file(&program);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let definition = Path::new(stdout.trim_end());
+    assert_eq!(
+        definition.file_name(),
+        library.file_name(),
+        "memcpy comes from {}",
+        definition.display()
+    );
+}
+
+#[test]
+fn preload() {
+    // Integration tests run from `target/<profile>/deps`, where Cargo also puts the library.
+    let executable = env::current_exe().unwrap();
+    let library = executable.parent().unwrap().join("libmemcpy_preload.so");
+    assert!(library.exists(), "{} was not built", library.display());
+    run(&library, "native");
+}
+
+#[test]
+fn preload_portable() {
+    // Every size goes through the loops of the portable kernels, which the optimiser would turn
+    // into calls to the preloaded functions themselves if the crates allowed it. A target directory
+    // of its own keeps the build from waiting on the lock of the one running the tests.
+    let executable = env::current_exe().unwrap();
+    let target = executable
+        .ancestors()
+        .nth(3)
+        .unwrap()
+        .join("preload-portable");
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args([
+            "build",
+            "--release",
+            "--features",
+            "portable",
+            "--target-dir",
+        ])
+        .arg(&target)
+        .status()
+        .unwrap();
+    assert!(status.success(), "cannot build the portable library");
+    run(&target.join("release/libmemcpy_preload.so"), "portable");
+}
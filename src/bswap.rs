@@ -0,0 +1,902 @@
+#[cfg(any(
+    target_feature = "movbe",
+    target_feature = "ssse3",
+    target_feature = "avx2"
+))]
+use core::arch::asm;
+use core::arch::x86_64::{__m128i, _mm_loadu_si128};
+#[cfg(target_feature = "avx2")]
+use core::arch::x86_64::{__m256i, _mm256_loadu_si256};
+#[cfg(target_feature = "movbe")]
+use core::ptr;
+
+// The scalar kernels load through `movbe`. Two 16-bit elements are swapped at once by
+// byte-reversing a 32-bit word and rotating it by 16, so that every form, including `movnti`,
+// stores 32 bits. Without pipelining, an odd last element is swapped on its own after the words.
+//
+// The loops run at least once, so every kernel returns early when there is nothing to copy.
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap16_movbe(size: usize, source: *mut u8, destination: *mut u8) {
+    let words = size / 4;
+    if words != 0 {
+        unsafe {
+            asm!(
+                "2:",
+                "    movbe {temp:e}, [{source} + {counter:r} * 4]",
+                "    rol {temp:e}, 16",
+                "    mov [{destination} + {counter:r} * 4], {temp:e}",
+                "    inc {counter:r}",
+                "    cmp {counter:r}, {size}",
+                "    jne 2b",
+                source = in(reg) source,
+                destination = in(reg) destination,
+                counter = inout(reg) 0 => _,
+                size = in(reg) words,
+                temp = out(reg) _,
+                options(nostack),
+            );
+        }
+    }
+    copy_odd_bswap16(size, source, destination);
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap32_movbe(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp:e}, [{source} + {counter:r} * 4]",
+            "    mov [{destination} + {counter:r} * 4], {temp:e}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 4,
+            temp = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap64_movbe(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp:r}, [{source} + {counter:r} * 8]",
+            "    mov [{destination} + {counter:r} * 8], {temp:r}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            temp = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap16_movbe_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp0:e}, [{source} + {counter:r}]",
+            "    movbe {temp1:e}, [{source} + {counter:r} + 4]",
+            "    movbe {temp2:e}, [{source} + {counter:r} + 8]",
+            "    movbe {temp3:e}, [{source} + {counter:r} + 12]",
+            "    movbe {temp4:e}, [{source} + {counter:r} + 16]",
+            "    movbe {temp5:e}, [{source} + {counter:r} + 20]",
+            "    movbe {temp6:e}, [{source} + {counter:r} + 24]",
+            "    movbe {temp7:e}, [{source} + {counter:r} + 28]",
+            "    rol {temp0:e}, 16",
+            "    rol {temp1:e}, 16",
+            "    rol {temp2:e}, 16",
+            "    rol {temp3:e}, 16",
+            "    rol {temp4:e}, 16",
+            "    rol {temp5:e}, 16",
+            "    rol {temp6:e}, 16",
+            "    rol {temp7:e}, 16",
+            "    mov [{destination} + {counter:r}], {temp0:e}",
+            "    mov [{destination} + {counter:r} + 4], {temp1:e}",
+            "    mov [{destination} + {counter:r} + 8], {temp2:e}",
+            "    mov [{destination} + {counter:r} + 12], {temp3:e}",
+            "    mov [{destination} + {counter:r} + 16], {temp4:e}",
+            "    mov [{destination} + {counter:r} + 20], {temp5:e}",
+            "    mov [{destination} + {counter:r} + 24], {temp6:e}",
+            "    mov [{destination} + {counter:r} + 28], {temp7:e}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp0 = out(reg) _,
+            temp1 = out(reg) _,
+            temp2 = out(reg) _,
+            temp3 = out(reg) _,
+            temp4 = out(reg) _,
+            temp5 = out(reg) _,
+            temp6 = out(reg) _,
+            temp7 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap32_movbe_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp0:e}, [{source} + {counter:r}]",
+            "    movbe {temp1:e}, [{source} + {counter:r} + 4]",
+            "    movbe {temp2:e}, [{source} + {counter:r} + 8]",
+            "    movbe {temp3:e}, [{source} + {counter:r} + 12]",
+            "    movbe {temp4:e}, [{source} + {counter:r} + 16]",
+            "    movbe {temp5:e}, [{source} + {counter:r} + 20]",
+            "    movbe {temp6:e}, [{source} + {counter:r} + 24]",
+            "    movbe {temp7:e}, [{source} + {counter:r} + 28]",
+            "    mov [{destination} + {counter:r}], {temp0:e}",
+            "    mov [{destination} + {counter:r} + 4], {temp1:e}",
+            "    mov [{destination} + {counter:r} + 8], {temp2:e}",
+            "    mov [{destination} + {counter:r} + 12], {temp3:e}",
+            "    mov [{destination} + {counter:r} + 16], {temp4:e}",
+            "    mov [{destination} + {counter:r} + 20], {temp5:e}",
+            "    mov [{destination} + {counter:r} + 24], {temp6:e}",
+            "    mov [{destination} + {counter:r} + 28], {temp7:e}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp0 = out(reg) _,
+            temp1 = out(reg) _,
+            temp2 = out(reg) _,
+            temp3 = out(reg) _,
+            temp4 = out(reg) _,
+            temp5 = out(reg) _,
+            temp6 = out(reg) _,
+            temp7 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap64_movbe_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp0:r}, [{source} + {counter:r}]",
+            "    movbe {temp1:r}, [{source} + {counter:r} + 8]",
+            "    movbe {temp2:r}, [{source} + {counter:r} + 16]",
+            "    movbe {temp3:r}, [{source} + {counter:r} + 24]",
+            "    movbe {temp4:r}, [{source} + {counter:r} + 32]",
+            "    movbe {temp5:r}, [{source} + {counter:r} + 40]",
+            "    movbe {temp6:r}, [{source} + {counter:r} + 48]",
+            "    movbe {temp7:r}, [{source} + {counter:r} + 56]",
+            "    mov [{destination} + {counter:r}], {temp0:r}",
+            "    mov [{destination} + {counter:r} + 8], {temp1:r}",
+            "    mov [{destination} + {counter:r} + 16], {temp2:r}",
+            "    mov [{destination} + {counter:r} + 24], {temp3:r}",
+            "    mov [{destination} + {counter:r} + 32], {temp4:r}",
+            "    mov [{destination} + {counter:r} + 40], {temp5:r}",
+            "    mov [{destination} + {counter:r} + 48], {temp6:r}",
+            "    mov [{destination} + {counter:r} + 56], {temp7:r}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp0 = out(reg) _,
+            temp1 = out(reg) _,
+            temp2 = out(reg) _,
+            temp3 = out(reg) _,
+            temp4 = out(reg) _,
+            temp5 = out(reg) _,
+            temp6 = out(reg) _,
+            temp7 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap16_movbe_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    let words = size / 4;
+    if words != 0 {
+        unsafe {
+            asm!(
+                "2:",
+                "    movbe {temp:e}, [{source} + {counter:r} * 4]",
+                "    rol {temp:e}, 16",
+                "    movnti [{destination} + {counter:r} * 4], {temp:e}",
+                "    inc {counter:r}",
+                "    cmp {counter:r}, {size}",
+                "    jne 2b",
+                source = in(reg) source,
+                destination = in(reg) destination,
+                counter = inout(reg) 0 => _,
+                size = in(reg) words,
+                temp = out(reg) _,
+                options(nostack),
+            );
+        }
+    }
+    copy_odd_bswap16(size, source, destination);
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap32_movbe_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp:e}, [{source} + {counter:r} * 4]",
+            "    movnti [{destination} + {counter:r} * 4], {temp:e}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 4,
+            temp = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap64_movbe_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp:r}, [{source} + {counter:r} * 8]",
+            "    movnti [{destination} + {counter:r} * 8], {temp:r}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            temp = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap16_movbe_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp0:e}, [{source} + {counter:r}]",
+            "    movbe {temp1:e}, [{source} + {counter:r} + 4]",
+            "    movbe {temp2:e}, [{source} + {counter:r} + 8]",
+            "    movbe {temp3:e}, [{source} + {counter:r} + 12]",
+            "    movbe {temp4:e}, [{source} + {counter:r} + 16]",
+            "    movbe {temp5:e}, [{source} + {counter:r} + 20]",
+            "    movbe {temp6:e}, [{source} + {counter:r} + 24]",
+            "    movbe {temp7:e}, [{source} + {counter:r} + 28]",
+            "    rol {temp0:e}, 16",
+            "    rol {temp1:e}, 16",
+            "    rol {temp2:e}, 16",
+            "    rol {temp3:e}, 16",
+            "    rol {temp4:e}, 16",
+            "    rol {temp5:e}, 16",
+            "    rol {temp6:e}, 16",
+            "    rol {temp7:e}, 16",
+            "    movnti [{destination} + {counter:r}], {temp0:e}",
+            "    movnti [{destination} + {counter:r} + 4], {temp1:e}",
+            "    movnti [{destination} + {counter:r} + 8], {temp2:e}",
+            "    movnti [{destination} + {counter:r} + 12], {temp3:e}",
+            "    movnti [{destination} + {counter:r} + 16], {temp4:e}",
+            "    movnti [{destination} + {counter:r} + 20], {temp5:e}",
+            "    movnti [{destination} + {counter:r} + 24], {temp6:e}",
+            "    movnti [{destination} + {counter:r} + 28], {temp7:e}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp0 = out(reg) _,
+            temp1 = out(reg) _,
+            temp2 = out(reg) _,
+            temp3 = out(reg) _,
+            temp4 = out(reg) _,
+            temp5 = out(reg) _,
+            temp6 = out(reg) _,
+            temp7 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap32_movbe_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp0:e}, [{source} + {counter:r}]",
+            "    movbe {temp1:e}, [{source} + {counter:r} + 4]",
+            "    movbe {temp2:e}, [{source} + {counter:r} + 8]",
+            "    movbe {temp3:e}, [{source} + {counter:r} + 12]",
+            "    movbe {temp4:e}, [{source} + {counter:r} + 16]",
+            "    movbe {temp5:e}, [{source} + {counter:r} + 20]",
+            "    movbe {temp6:e}, [{source} + {counter:r} + 24]",
+            "    movbe {temp7:e}, [{source} + {counter:r} + 28]",
+            "    movnti [{destination} + {counter:r}], {temp0:e}",
+            "    movnti [{destination} + {counter:r} + 4], {temp1:e}",
+            "    movnti [{destination} + {counter:r} + 8], {temp2:e}",
+            "    movnti [{destination} + {counter:r} + 12], {temp3:e}",
+            "    movnti [{destination} + {counter:r} + 16], {temp4:e}",
+            "    movnti [{destination} + {counter:r} + 20], {temp5:e}",
+            "    movnti [{destination} + {counter:r} + 24], {temp6:e}",
+            "    movnti [{destination} + {counter:r} + 28], {temp7:e}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp0 = out(reg) _,
+            temp1 = out(reg) _,
+            temp2 = out(reg) _,
+            temp3 = out(reg) _,
+            temp4 = out(reg) _,
+            temp5 = out(reg) _,
+            temp6 = out(reg) _,
+            temp7 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "movbe")]
+pub fn copy_bswap64_movbe_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movbe {temp0:r}, [{source} + {counter:r}]",
+            "    movbe {temp1:r}, [{source} + {counter:r} + 8]",
+            "    movbe {temp2:r}, [{source} + {counter:r} + 16]",
+            "    movbe {temp3:r}, [{source} + {counter:r} + 24]",
+            "    movbe {temp4:r}, [{source} + {counter:r} + 32]",
+            "    movbe {temp5:r}, [{source} + {counter:r} + 40]",
+            "    movbe {temp6:r}, [{source} + {counter:r} + 48]",
+            "    movbe {temp7:r}, [{source} + {counter:r} + 56]",
+            "    movnti [{destination} + {counter:r}], {temp0:r}",
+            "    movnti [{destination} + {counter:r} + 8], {temp1:r}",
+            "    movnti [{destination} + {counter:r} + 16], {temp2:r}",
+            "    movnti [{destination} + {counter:r} + 24], {temp3:r}",
+            "    movnti [{destination} + {counter:r} + 32], {temp4:r}",
+            "    movnti [{destination} + {counter:r} + 40], {temp5:r}",
+            "    movnti [{destination} + {counter:r} + 48], {temp6:r}",
+            "    movnti [{destination} + {counter:r} + 56], {temp7:r}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp0 = out(reg) _,
+            temp1 = out(reg) _,
+            temp2 = out(reg) _,
+            temp3 = out(reg) _,
+            temp4 = out(reg) _,
+            temp5 = out(reg) _,
+            temp6 = out(reg) _,
+            temp7 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+/// Swaps the last element when there is an odd number of 16-bit elements, which the words leave
+/// out.
+#[cfg(target_feature = "movbe")]
+fn copy_odd_bswap16(size: usize, source: *mut u8, destination: *mut u8) {
+    if size % 4 == 2 {
+        unsafe {
+            let element = ptr::read_unaligned(source.add(size - 2) as *const u16);
+            ptr::write_unaligned(destination.add(size - 2) as *mut u16, element.swap_bytes());
+        }
+    }
+}
+
+fn shuffle_mask(element: usize) -> [u8; 32] {
+    let mut mask = [0; 32];
+    for (i, byte) in mask.iter_mut().enumerate() {
+        let lane = i % 16;
+        *byte = (lane - lane % element + element - 1 - lane % element) as u8;
+    }
+    mask
+}
+
+#[cfg(target_feature = "ssse3")]
+fn shuffle_mask_128(element: usize) -> __m128i {
+    unsafe { _mm_loadu_si128(shuffle_mask(element).as_ptr() as *const __m128i) }
+}
+
+#[cfg(target_feature = "avx2")]
+fn shuffle_mask_256(element: usize) -> __m256i {
+    unsafe { _mm256_loadu_si256(shuffle_mask(element).as_ptr() as *const __m256i) }
+}
+
+#[cfg(target_feature = "ssse3")]
+fn copy_pshufb_128(size: usize, source: *mut u8, destination: *mut u8, mask: __m128i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movdqa {temp}, [{source} + {counter:r}]",
+            "    pshufb {temp}, {mask}",
+            "    movdqa [{destination} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(xmm_reg) mask,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "ssse3")]
+fn copy_pshufb_128_pl(size: usize, source: *mut u8, destination: *mut u8, mask: __m128i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movdqa xmm0, [{source} + {counter:r}]",
+            "    movdqa xmm1, [{source} + {counter:r} + 16]",
+            "    movdqa xmm2, [{source} + {counter:r} + 32]",
+            "    movdqa xmm3, [{source} + {counter:r} + 48]",
+            "    movdqa xmm4, [{source} + {counter:r} + 64]",
+            "    movdqa xmm5, [{source} + {counter:r} + 80]",
+            "    movdqa xmm6, [{source} + {counter:r} + 96]",
+            "    movdqa xmm7, [{source} + {counter:r} + 112]",
+            "    pshufb xmm0, {mask}",
+            "    pshufb xmm1, {mask}",
+            "    pshufb xmm2, {mask}",
+            "    pshufb xmm3, {mask}",
+            "    pshufb xmm4, {mask}",
+            "    pshufb xmm5, {mask}",
+            "    pshufb xmm6, {mask}",
+            "    pshufb xmm7, {mask}",
+            "    movdqa [{destination} + {counter:r}], xmm0",
+            "    movdqa [{destination} + {counter:r} + 16], xmm1",
+            "    movdqa [{destination} + {counter:r} + 32], xmm2",
+            "    movdqa [{destination} + {counter:r} + 48], xmm3",
+            "    movdqa [{destination} + {counter:r} + 64], xmm4",
+            "    movdqa [{destination} + {counter:r} + 80], xmm5",
+            "    movdqa [{destination} + {counter:r} + 96], xmm6",
+            "    movdqa [{destination} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(xmm_reg) mask,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "ssse3")]
+fn copy_pshufb_128_nt(size: usize, source: *mut u8, destination: *mut u8, mask: __m128i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movdqa {temp}, [{source} + {counter:r}]",
+            "    pshufb {temp}, {mask}",
+            "    movntdq [{destination} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(xmm_reg) mask,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "ssse3")]
+fn copy_pshufb_128_nt_pl(size: usize, source: *mut u8, destination: *mut u8, mask: __m128i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    movdqa xmm0, [{source} + {counter:r}]",
+            "    movdqa xmm1, [{source} + {counter:r} + 16]",
+            "    movdqa xmm2, [{source} + {counter:r} + 32]",
+            "    movdqa xmm3, [{source} + {counter:r} + 48]",
+            "    movdqa xmm4, [{source} + {counter:r} + 64]",
+            "    movdqa xmm5, [{source} + {counter:r} + 80]",
+            "    movdqa xmm6, [{source} + {counter:r} + 96]",
+            "    movdqa xmm7, [{source} + {counter:r} + 112]",
+            "    pshufb xmm0, {mask}",
+            "    pshufb xmm1, {mask}",
+            "    pshufb xmm2, {mask}",
+            "    pshufb xmm3, {mask}",
+            "    pshufb xmm4, {mask}",
+            "    pshufb xmm5, {mask}",
+            "    pshufb xmm6, {mask}",
+            "    pshufb xmm7, {mask}",
+            "    movntdq [{destination} + {counter:r}], xmm0",
+            "    movntdq [{destination} + {counter:r} + 16], xmm1",
+            "    movntdq [{destination} + {counter:r} + 32], xmm2",
+            "    movntdq [{destination} + {counter:r} + 48], xmm3",
+            "    movntdq [{destination} + {counter:r} + 64], xmm4",
+            "    movntdq [{destination} + {counter:r} + 80], xmm5",
+            "    movntdq [{destination} + {counter:r} + 96], xmm6",
+            "    movntdq [{destination} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(xmm_reg) mask,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx2")]
+fn copy_vpshufb_256(size: usize, source: *mut u8, destination: *mut u8, mask: __m256i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovdqa {temp}, [{source} + {counter:r}]",
+            "    vpshufb {temp}, {temp}, {mask}",
+            "    vmovdqa [{destination} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(ymm_reg) mask,
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx2")]
+fn copy_vpshufb_256_pl(size: usize, source: *mut u8, destination: *mut u8, mask: __m256i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovdqa ymm0, [{source} + {counter:r}]",
+            "    vmovdqa ymm1, [{source} + {counter:r} + 32]",
+            "    vmovdqa ymm2, [{source} + {counter:r} + 64]",
+            "    vmovdqa ymm3, [{source} + {counter:r} + 96]",
+            "    vmovdqa ymm4, [{source} + {counter:r} + 128]",
+            "    vmovdqa ymm5, [{source} + {counter:r} + 160]",
+            "    vmovdqa ymm6, [{source} + {counter:r} + 192]",
+            "    vmovdqa ymm7, [{source} + {counter:r} + 224]",
+            "    vpshufb ymm0, ymm0, {mask}",
+            "    vpshufb ymm1, ymm1, {mask}",
+            "    vpshufb ymm2, ymm2, {mask}",
+            "    vpshufb ymm3, ymm3, {mask}",
+            "    vpshufb ymm4, ymm4, {mask}",
+            "    vpshufb ymm5, ymm5, {mask}",
+            "    vpshufb ymm6, ymm6, {mask}",
+            "    vpshufb ymm7, ymm7, {mask}",
+            "    vmovdqa [{destination} + {counter:r}], ymm0",
+            "    vmovdqa [{destination} + {counter:r} + 32], ymm1",
+            "    vmovdqa [{destination} + {counter:r} + 64], ymm2",
+            "    vmovdqa [{destination} + {counter:r} + 96], ymm3",
+            "    vmovdqa [{destination} + {counter:r} + 128], ymm4",
+            "    vmovdqa [{destination} + {counter:r} + 160], ymm5",
+            "    vmovdqa [{destination} + {counter:r} + 192], ymm6",
+            "    vmovdqa [{destination} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(ymm_reg) mask,
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx2")]
+fn copy_vpshufb_256_nt(size: usize, source: *mut u8, destination: *mut u8, mask: __m256i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovdqa {temp}, [{source} + {counter:r}]",
+            "    vpshufb {temp}, {temp}, {mask}",
+            "    vmovntdq [{destination} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(ymm_reg) mask,
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx2")]
+fn copy_vpshufb_256_nt_pl(size: usize, source: *mut u8, destination: *mut u8, mask: __m256i) {
+    if size == 0 {
+        return;
+    }
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovdqa ymm0, [{source} + {counter:r}]",
+            "    vmovdqa ymm1, [{source} + {counter:r} + 32]",
+            "    vmovdqa ymm2, [{source} + {counter:r} + 64]",
+            "    vmovdqa ymm3, [{source} + {counter:r} + 96]",
+            "    vmovdqa ymm4, [{source} + {counter:r} + 128]",
+            "    vmovdqa ymm5, [{source} + {counter:r} + 160]",
+            "    vmovdqa ymm6, [{source} + {counter:r} + 192]",
+            "    vmovdqa ymm7, [{source} + {counter:r} + 224]",
+            "    vpshufb ymm0, ymm0, {mask}",
+            "    vpshufb ymm1, ymm1, {mask}",
+            "    vpshufb ymm2, ymm2, {mask}",
+            "    vpshufb ymm3, ymm3, {mask}",
+            "    vpshufb ymm4, ymm4, {mask}",
+            "    vpshufb ymm5, ymm5, {mask}",
+            "    vpshufb ymm6, ymm6, {mask}",
+            "    vpshufb ymm7, ymm7, {mask}",
+            "    vmovntdq [{destination} + {counter:r}], ymm0",
+            "    vmovntdq [{destination} + {counter:r} + 32], ymm1",
+            "    vmovntdq [{destination} + {counter:r} + 64], ymm2",
+            "    vmovntdq [{destination} + {counter:r} + 96], ymm3",
+            "    vmovntdq [{destination} + {counter:r} + 128], ymm4",
+            "    vmovntdq [{destination} + {counter:r} + 160], ymm5",
+            "    vmovntdq [{destination} + {counter:r} + 192], ymm6",
+            "    vmovntdq [{destination} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            source = in(reg) source,
+            destination = in(reg) destination,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            mask = in(ymm_reg) mask,
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap16_128(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128(size, source, destination, shuffle_mask_128(2))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap32_128(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128(size, source, destination, shuffle_mask_128(4))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap64_128(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128(size, source, destination, shuffle_mask_128(8))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap16_128_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_pl(size, source, destination, shuffle_mask_128(2))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap32_128_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_pl(size, source, destination, shuffle_mask_128(4))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap64_128_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_pl(size, source, destination, shuffle_mask_128(8))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap16_128_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_nt(size, source, destination, shuffle_mask_128(2))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap32_128_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_nt(size, source, destination, shuffle_mask_128(4))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap64_128_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_nt(size, source, destination, shuffle_mask_128(8))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap16_128_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_nt_pl(size, source, destination, shuffle_mask_128(2))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap32_128_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_nt_pl(size, source, destination, shuffle_mask_128(4))
+}
+
+#[cfg(target_feature = "ssse3")]
+pub fn copy_bswap64_128_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pshufb_128_nt_pl(size, source, destination, shuffle_mask_128(8))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap16_256(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256(size, source, destination, shuffle_mask_256(2))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap32_256(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256(size, source, destination, shuffle_mask_256(4))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap64_256(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256(size, source, destination, shuffle_mask_256(8))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap16_256_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_pl(size, source, destination, shuffle_mask_256(2))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap32_256_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_pl(size, source, destination, shuffle_mask_256(4))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap64_256_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_pl(size, source, destination, shuffle_mask_256(8))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap16_256_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_nt(size, source, destination, shuffle_mask_256(2))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap32_256_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_nt(size, source, destination, shuffle_mask_256(4))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap64_256_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_nt(size, source, destination, shuffle_mask_256(8))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap16_256_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_nt_pl(size, source, destination, shuffle_mask_256(2))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap32_256_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_nt_pl(size, source, destination, shuffle_mask_256(4))
+}
+
+#[cfg(target_feature = "avx2")]
+pub fn copy_bswap64_256_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_vpshufb_256_nt_pl(size, source, destination, shuffle_mask_256(8))
+}
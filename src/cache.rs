@@ -0,0 +1,72 @@
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+use core::arch::asm;
+#[cfg(not(all(target_arch = "x86_64", not(miri))))]
+use std::hint::black_box;
+
+// Flushing buffers from the caches, so that the benchmarks can time a copy that finds its buffers
+// in memory like a first copy does.
+
+/// Returns the function that flushes the lines of a buffer from every cache, with `clflushopt` if
+/// the processor has it, which unlike `clflush` does not wait for a line to be written back before
+/// flushing the next. Looking the instruction up is slow, so callers keep the function.
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+pub fn flusher() -> fn(*const u8, usize) {
+    use core::arch::x86_64::{__cpuid_count, __get_cpuid_max};
+
+    // Leaf 7 of CPUID has `clflushopt` in bit 23 of EBX. Newer toolchains made these intrinsics
+    // safe, hence the allowance for an `unsafe` they no longer need.
+    #[allow(unused_unsafe)]
+    let optimized = unsafe { __get_cpuid_max(0).0 >= 7 && __cpuid_count(7, 0).ebx & 1 << 23 != 0 };
+    if optimized {
+        flush_optimized
+    } else {
+        flush
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+fn flush(buffer: *const u8, size: usize) {
+    use core::arch::x86_64::{_mm_clflush, _mm_mfence};
+
+    for line in lines(buffer, size) {
+        unsafe { _mm_clflush(line) };
+    }
+    unsafe { _mm_mfence() };
+}
+
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+fn flush_optimized(buffer: *const u8, size: usize) {
+    use core::arch::x86_64::_mm_mfence;
+
+    for line in lines(buffer, size) {
+        unsafe { asm!("clflushopt [{}]", in(reg) line, options(nostack, preserves_flags)) };
+    }
+    // Unlike `clflush`, `clflushopt` is only ordered with the copy by a fence.
+    unsafe { _mm_mfence() };
+}
+
+/// The start of every cache line of a buffer, from the line it starts in, which is before it when
+/// it is unaligned.
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+fn lines(buffer: *const u8, size: usize) -> impl Iterator<Item = *const u8> {
+    let start = buffer as usize & !63;
+    (start..buffer as usize + size)
+        .step_by(64)
+        .map(|line| line as *const u8)
+}
+
+#[cfg(not(all(target_arch = "x86_64", not(miri))))]
+pub fn flusher() -> fn(*const u8, usize) {
+    flush
+}
+
+/// Without an instruction to flush a line, writing over more memory than the caches hold evicts
+/// everything else from them.
+#[cfg(not(all(target_arch = "x86_64", not(miri))))]
+fn flush(_buffer: *const u8, _size: usize) {
+    let mut scratch = vec![0u8; 256 << 20];
+    for byte in scratch.iter_mut().step_by(64) {
+        *byte = 1;
+    }
+    black_box(&scratch);
+}
@@ -0,0 +1,94 @@
+use crate::backend::sfence;
+use crate::{memcmp_mov_64, memcpy_rep_movsb, memmove_rep_movsb, memset_rep_stosb};
+
+// Unlike the kernels, the dispatcher accepts any size and alignment, so it is what the exported
+// `memcpy`, `memmove`, `memset` and `memcmp` symbols route through. It must never call into libc,
+// directly or through code the compiler lowers to a call to `memcpy`, since it may itself be that
+// `memcpy`.
+
+#[cfg(not(target_feature = "avx"))]
+use crate::{
+    memcpy_mov_128_nt_pl as copy_block_nt, memcpy_mov_128_pl as copy_block,
+    memcpy_movu_128_nt_pl as copy_block_nt_unaligned,
+};
+#[cfg(target_feature = "avx")]
+use crate::{
+    memcpy_mov_256_nt_pl as copy_block_nt, memcpy_mov_256_pl as copy_block,
+    memcpy_movu_256_nt_pl as copy_block_nt_unaligned,
+};
+
+#[cfg(target_feature = "avx")]
+const ALIGNMENT: usize = 32;
+#[cfg(not(target_feature = "avx"))]
+const ALIGNMENT: usize = 16;
+
+/// The number of bytes each iteration of the pipelined kernels copies.
+const BLOCK: usize = 8 * ALIGNMENT;
+
+/// Below this size, `rep movsb` beats aligning the buffers. It also leaves at least one block
+/// between the head and the tail.
+const SMALL: usize = 2 * BLOCK;
+
+/// Past this size, the source and destination together no longer fit in 16 MiB of last-level cache,
+/// and non-temporal stores win. That is the whole L3 of the Coffee Lake in the benchmark, and the
+/// slice of the 64 MiB L3 of the Zen 2 that its core complex, and so a single thread, can use.
+const NON_TEMPORAL: usize = 8 << 20;
+
+pub fn memcpy(size: usize, source: *mut u8, destination: *mut u8) {
+    copy(size, source, destination, size >= NON_TEMPORAL);
+}
+
+/// Like `memcpy`, but with non-temporal stores whatever the size, for data the caller will not read
+/// back soon.
+pub fn memcpy_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    copy(size, source, destination, true);
+}
+
+fn copy(size: usize, source: *mut u8, destination: *mut u8, non_temporal: bool) {
+    // Only the non-temporal kernels have a variant for sources misaligned with the destination.
+    let misalignment = destination as usize % ALIGNMENT;
+    let aligned = source as usize % ALIGNMENT == misalignment;
+    if size < SMALL || !(aligned || non_temporal) {
+        memcpy_rep_movsb(size, source, destination);
+        return;
+    }
+
+    let head = (ALIGNMENT - misalignment) % ALIGNMENT;
+    let body = (size - head) / BLOCK * BLOCK;
+    let tail = size - head - body;
+    memcpy_rep_movsb(head, source, destination);
+    let (source_body, destination_body) =
+        (source.wrapping_add(head), destination.wrapping_add(head));
+    if non_temporal {
+        if aligned {
+            copy_block_nt(body, source_body, destination_body);
+        } else {
+            copy_block_nt_unaligned(body, source_body, destination_body);
+        }
+        sfence();
+    } else {
+        copy_block(body, source_body, destination_body);
+    }
+    memcpy_rep_movsb(
+        tail,
+        source.wrapping_add(head + body),
+        destination.wrapping_add(head + body),
+    );
+}
+
+pub fn memmove(size: usize, source: *mut u8, destination: *mut u8) {
+    // Copying forward is only wrong when the destination starts inside the source.
+    if (destination as usize).wrapping_sub(source as usize) >= size {
+        memcpy(size, source, destination);
+    } else {
+        memmove_rep_movsb(size, source, destination);
+    }
+}
+
+pub fn memset(size: usize, value: u8, destination: *mut u8) {
+    memset_rep_stosb(size, value, destination);
+}
+
+pub fn memcmp(size: usize, left: *const u8, right: *const u8) -> i32 {
+    memcmp_mov_64(size, left, right)
+}
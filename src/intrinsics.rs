@@ -0,0 +1,395 @@
+use core::arch::x86_64::{
+    __m128i, _mm_load_si128, _mm_prefetch, _mm_store_si128, _mm_stream_si128, _mm_stream_si64,
+    _MM_HINT_NTA, _MM_HINT_T0,
+};
+#[cfg(target_feature = "avx")]
+use core::arch::x86_64::{__m256i, _mm256_load_si256, _mm256_store_si256, _mm256_stream_si256};
+use core::ptr;
+
+// The `memcpy_mov_*` kernels again, with `core::arch` intrinsics instead of assembly, so that the
+// benchmark tells what writing the loops by hand is worth over letting the compiler schedule them.
+// They copy the same words with the same instructions, and the pipelined ones unroll the same eight
+// loads before the eight stores; the compiler is free to interleave them. The scalar moves have no
+// intrinsic, so the scalar kernels copy through volatile accesses, which keep the compiler from
+// widening or merging them the way it does for `memcpy_loop_64`, but need aligned words.
+//
+// The `_pf` kernels are the pipelined ones with `_mm_prefetch` added, which the assembly kernels
+// do not have: each iteration prefetches the block the next one copies, one stride ahead, for the
+// caches with the temporal stores and past them with the non-temporal ones.
+
+/// Prefetches the `size` bytes from `block` a line at a time. Past the end of the source, the
+/// prefetches do nothing rather than fault.
+#[inline(always)]
+fn prefetch<const STRATEGY: i32>(block: *const u8, size: usize) {
+    for line in (0..size).step_by(64) {
+        unsafe { _mm_prefetch::<STRATEGY>(block.wrapping_add(line) as *const i8) };
+    }
+}
+
+pub fn memcpy_intrinsics_32(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const u32;
+    let destination = destination as *mut u32;
+    for i in 0..size / 4 {
+        unsafe { ptr::write_volatile(destination.add(i), ptr::read_volatile(source.add(i))) };
+    }
+}
+
+pub fn memcpy_intrinsics_64(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const u64;
+    let destination = destination as *mut u64;
+    for i in 0..size / 8 {
+        unsafe { ptr::write_volatile(destination.add(i), ptr::read_volatile(source.add(i))) };
+    }
+}
+
+pub fn memcpy_intrinsics_128(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m128i;
+    let destination = destination as *mut __m128i;
+    for i in 0..size / 16 {
+        unsafe { _mm_store_si128(destination.add(i), _mm_load_si128(source.add(i))) };
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn memcpy_intrinsics_256(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m256i;
+    let destination = destination as *mut __m256i;
+    for i in 0..size / 32 {
+        unsafe { _mm256_store_si256(destination.add(i), _mm256_load_si256(source.add(i))) };
+    }
+}
+
+pub fn memcpy_intrinsics_64_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const u64;
+    let destination = destination as *mut u64;
+    for i in (0..size / 8).step_by(8) {
+        unsafe {
+            let temp0 = ptr::read_volatile(source.add(i));
+            let temp1 = ptr::read_volatile(source.add(i + 1));
+            let temp2 = ptr::read_volatile(source.add(i + 2));
+            let temp3 = ptr::read_volatile(source.add(i + 3));
+            let temp4 = ptr::read_volatile(source.add(i + 4));
+            let temp5 = ptr::read_volatile(source.add(i + 5));
+            let temp6 = ptr::read_volatile(source.add(i + 6));
+            let temp7 = ptr::read_volatile(source.add(i + 7));
+            ptr::write_volatile(destination.add(i), temp0);
+            ptr::write_volatile(destination.add(i + 1), temp1);
+            ptr::write_volatile(destination.add(i + 2), temp2);
+            ptr::write_volatile(destination.add(i + 3), temp3);
+            ptr::write_volatile(destination.add(i + 4), temp4);
+            ptr::write_volatile(destination.add(i + 5), temp5);
+            ptr::write_volatile(destination.add(i + 6), temp6);
+            ptr::write_volatile(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_128_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m128i;
+    let destination = destination as *mut __m128i;
+    for i in (0..size / 16).step_by(8) {
+        unsafe {
+            let temp0 = _mm_load_si128(source.add(i));
+            let temp1 = _mm_load_si128(source.add(i + 1));
+            let temp2 = _mm_load_si128(source.add(i + 2));
+            let temp3 = _mm_load_si128(source.add(i + 3));
+            let temp4 = _mm_load_si128(source.add(i + 4));
+            let temp5 = _mm_load_si128(source.add(i + 5));
+            let temp6 = _mm_load_si128(source.add(i + 6));
+            let temp7 = _mm_load_si128(source.add(i + 7));
+            _mm_store_si128(destination.add(i), temp0);
+            _mm_store_si128(destination.add(i + 1), temp1);
+            _mm_store_si128(destination.add(i + 2), temp2);
+            _mm_store_si128(destination.add(i + 3), temp3);
+            _mm_store_si128(destination.add(i + 4), temp4);
+            _mm_store_si128(destination.add(i + 5), temp5);
+            _mm_store_si128(destination.add(i + 6), temp6);
+            _mm_store_si128(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn memcpy_intrinsics_256_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m256i;
+    let destination = destination as *mut __m256i;
+    for i in (0..size / 32).step_by(8) {
+        unsafe {
+            let temp0 = _mm256_load_si256(source.add(i));
+            let temp1 = _mm256_load_si256(source.add(i + 1));
+            let temp2 = _mm256_load_si256(source.add(i + 2));
+            let temp3 = _mm256_load_si256(source.add(i + 3));
+            let temp4 = _mm256_load_si256(source.add(i + 4));
+            let temp5 = _mm256_load_si256(source.add(i + 5));
+            let temp6 = _mm256_load_si256(source.add(i + 6));
+            let temp7 = _mm256_load_si256(source.add(i + 7));
+            _mm256_store_si256(destination.add(i), temp0);
+            _mm256_store_si256(destination.add(i + 1), temp1);
+            _mm256_store_si256(destination.add(i + 2), temp2);
+            _mm256_store_si256(destination.add(i + 3), temp3);
+            _mm256_store_si256(destination.add(i + 4), temp4);
+            _mm256_store_si256(destination.add(i + 5), temp5);
+            _mm256_store_si256(destination.add(i + 6), temp6);
+            _mm256_store_si256(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_64_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const i64;
+    let destination = destination as *mut i64;
+    for i in 0..size / 8 {
+        unsafe { _mm_stream_si64(destination.add(i), *source.add(i)) };
+    }
+}
+
+pub fn memcpy_intrinsics_128_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m128i;
+    let destination = destination as *mut __m128i;
+    for i in 0..size / 16 {
+        unsafe { _mm_stream_si128(destination.add(i), _mm_load_si128(source.add(i))) };
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn memcpy_intrinsics_256_nt(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m256i;
+    let destination = destination as *mut __m256i;
+    for i in 0..size / 32 {
+        unsafe { _mm256_stream_si256(destination.add(i), _mm256_load_si256(source.add(i))) };
+    }
+}
+
+pub fn memcpy_intrinsics_64_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const i64;
+    let destination = destination as *mut i64;
+    for i in (0..size / 8).step_by(8) {
+        unsafe {
+            let temp0 = *source.add(i);
+            let temp1 = *source.add(i + 1);
+            let temp2 = *source.add(i + 2);
+            let temp3 = *source.add(i + 3);
+            let temp4 = *source.add(i + 4);
+            let temp5 = *source.add(i + 5);
+            let temp6 = *source.add(i + 6);
+            let temp7 = *source.add(i + 7);
+            _mm_stream_si64(destination.add(i), temp0);
+            _mm_stream_si64(destination.add(i + 1), temp1);
+            _mm_stream_si64(destination.add(i + 2), temp2);
+            _mm_stream_si64(destination.add(i + 3), temp3);
+            _mm_stream_si64(destination.add(i + 4), temp4);
+            _mm_stream_si64(destination.add(i + 5), temp5);
+            _mm_stream_si64(destination.add(i + 6), temp6);
+            _mm_stream_si64(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_128_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m128i;
+    let destination = destination as *mut __m128i;
+    for i in (0..size / 16).step_by(8) {
+        unsafe {
+            let temp0 = _mm_load_si128(source.add(i));
+            let temp1 = _mm_load_si128(source.add(i + 1));
+            let temp2 = _mm_load_si128(source.add(i + 2));
+            let temp3 = _mm_load_si128(source.add(i + 3));
+            let temp4 = _mm_load_si128(source.add(i + 4));
+            let temp5 = _mm_load_si128(source.add(i + 5));
+            let temp6 = _mm_load_si128(source.add(i + 6));
+            let temp7 = _mm_load_si128(source.add(i + 7));
+            _mm_stream_si128(destination.add(i), temp0);
+            _mm_stream_si128(destination.add(i + 1), temp1);
+            _mm_stream_si128(destination.add(i + 2), temp2);
+            _mm_stream_si128(destination.add(i + 3), temp3);
+            _mm_stream_si128(destination.add(i + 4), temp4);
+            _mm_stream_si128(destination.add(i + 5), temp5);
+            _mm_stream_si128(destination.add(i + 6), temp6);
+            _mm_stream_si128(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn memcpy_intrinsics_256_nt_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m256i;
+    let destination = destination as *mut __m256i;
+    for i in (0..size / 32).step_by(8) {
+        unsafe {
+            let temp0 = _mm256_load_si256(source.add(i));
+            let temp1 = _mm256_load_si256(source.add(i + 1));
+            let temp2 = _mm256_load_si256(source.add(i + 2));
+            let temp3 = _mm256_load_si256(source.add(i + 3));
+            let temp4 = _mm256_load_si256(source.add(i + 4));
+            let temp5 = _mm256_load_si256(source.add(i + 5));
+            let temp6 = _mm256_load_si256(source.add(i + 6));
+            let temp7 = _mm256_load_si256(source.add(i + 7));
+            _mm256_stream_si256(destination.add(i), temp0);
+            _mm256_stream_si256(destination.add(i + 1), temp1);
+            _mm256_stream_si256(destination.add(i + 2), temp2);
+            _mm256_stream_si256(destination.add(i + 3), temp3);
+            _mm256_stream_si256(destination.add(i + 4), temp4);
+            _mm256_stream_si256(destination.add(i + 5), temp5);
+            _mm256_stream_si256(destination.add(i + 6), temp6);
+            _mm256_stream_si256(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_64_pl_pf(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const u64;
+    let destination = destination as *mut u64;
+    for i in (0..size / 8).step_by(8) {
+        unsafe {
+            prefetch::<_MM_HINT_T0>(source.wrapping_add(i + 8) as *const u8, 64);
+            let temp0 = ptr::read_volatile(source.add(i));
+            let temp1 = ptr::read_volatile(source.add(i + 1));
+            let temp2 = ptr::read_volatile(source.add(i + 2));
+            let temp3 = ptr::read_volatile(source.add(i + 3));
+            let temp4 = ptr::read_volatile(source.add(i + 4));
+            let temp5 = ptr::read_volatile(source.add(i + 5));
+            let temp6 = ptr::read_volatile(source.add(i + 6));
+            let temp7 = ptr::read_volatile(source.add(i + 7));
+            ptr::write_volatile(destination.add(i), temp0);
+            ptr::write_volatile(destination.add(i + 1), temp1);
+            ptr::write_volatile(destination.add(i + 2), temp2);
+            ptr::write_volatile(destination.add(i + 3), temp3);
+            ptr::write_volatile(destination.add(i + 4), temp4);
+            ptr::write_volatile(destination.add(i + 5), temp5);
+            ptr::write_volatile(destination.add(i + 6), temp6);
+            ptr::write_volatile(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_128_pl_pf(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m128i;
+    let destination = destination as *mut __m128i;
+    for i in (0..size / 16).step_by(8) {
+        unsafe {
+            prefetch::<_MM_HINT_T0>(source.wrapping_add(i + 8) as *const u8, 128);
+            let temp0 = _mm_load_si128(source.add(i));
+            let temp1 = _mm_load_si128(source.add(i + 1));
+            let temp2 = _mm_load_si128(source.add(i + 2));
+            let temp3 = _mm_load_si128(source.add(i + 3));
+            let temp4 = _mm_load_si128(source.add(i + 4));
+            let temp5 = _mm_load_si128(source.add(i + 5));
+            let temp6 = _mm_load_si128(source.add(i + 6));
+            let temp7 = _mm_load_si128(source.add(i + 7));
+            _mm_store_si128(destination.add(i), temp0);
+            _mm_store_si128(destination.add(i + 1), temp1);
+            _mm_store_si128(destination.add(i + 2), temp2);
+            _mm_store_si128(destination.add(i + 3), temp3);
+            _mm_store_si128(destination.add(i + 4), temp4);
+            _mm_store_si128(destination.add(i + 5), temp5);
+            _mm_store_si128(destination.add(i + 6), temp6);
+            _mm_store_si128(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn memcpy_intrinsics_256_pl_pf(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m256i;
+    let destination = destination as *mut __m256i;
+    for i in (0..size / 32).step_by(8) {
+        unsafe {
+            prefetch::<_MM_HINT_T0>(source.wrapping_add(i + 8) as *const u8, 256);
+            let temp0 = _mm256_load_si256(source.add(i));
+            let temp1 = _mm256_load_si256(source.add(i + 1));
+            let temp2 = _mm256_load_si256(source.add(i + 2));
+            let temp3 = _mm256_load_si256(source.add(i + 3));
+            let temp4 = _mm256_load_si256(source.add(i + 4));
+            let temp5 = _mm256_load_si256(source.add(i + 5));
+            let temp6 = _mm256_load_si256(source.add(i + 6));
+            let temp7 = _mm256_load_si256(source.add(i + 7));
+            _mm256_store_si256(destination.add(i), temp0);
+            _mm256_store_si256(destination.add(i + 1), temp1);
+            _mm256_store_si256(destination.add(i + 2), temp2);
+            _mm256_store_si256(destination.add(i + 3), temp3);
+            _mm256_store_si256(destination.add(i + 4), temp4);
+            _mm256_store_si256(destination.add(i + 5), temp5);
+            _mm256_store_si256(destination.add(i + 6), temp6);
+            _mm256_store_si256(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_64_nt_pl_pf(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const i64;
+    let destination = destination as *mut i64;
+    for i in (0..size / 8).step_by(8) {
+        unsafe {
+            prefetch::<_MM_HINT_NTA>(source.wrapping_add(i + 8) as *const u8, 64);
+            let temp0 = *source.add(i);
+            let temp1 = *source.add(i + 1);
+            let temp2 = *source.add(i + 2);
+            let temp3 = *source.add(i + 3);
+            let temp4 = *source.add(i + 4);
+            let temp5 = *source.add(i + 5);
+            let temp6 = *source.add(i + 6);
+            let temp7 = *source.add(i + 7);
+            _mm_stream_si64(destination.add(i), temp0);
+            _mm_stream_si64(destination.add(i + 1), temp1);
+            _mm_stream_si64(destination.add(i + 2), temp2);
+            _mm_stream_si64(destination.add(i + 3), temp3);
+            _mm_stream_si64(destination.add(i + 4), temp4);
+            _mm_stream_si64(destination.add(i + 5), temp5);
+            _mm_stream_si64(destination.add(i + 6), temp6);
+            _mm_stream_si64(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+pub fn memcpy_intrinsics_128_nt_pl_pf(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m128i;
+    let destination = destination as *mut __m128i;
+    for i in (0..size / 16).step_by(8) {
+        unsafe {
+            prefetch::<_MM_HINT_NTA>(source.wrapping_add(i + 8) as *const u8, 128);
+            let temp0 = _mm_load_si128(source.add(i));
+            let temp1 = _mm_load_si128(source.add(i + 1));
+            let temp2 = _mm_load_si128(source.add(i + 2));
+            let temp3 = _mm_load_si128(source.add(i + 3));
+            let temp4 = _mm_load_si128(source.add(i + 4));
+            let temp5 = _mm_load_si128(source.add(i + 5));
+            let temp6 = _mm_load_si128(source.add(i + 6));
+            let temp7 = _mm_load_si128(source.add(i + 7));
+            _mm_stream_si128(destination.add(i), temp0);
+            _mm_stream_si128(destination.add(i + 1), temp1);
+            _mm_stream_si128(destination.add(i + 2), temp2);
+            _mm_stream_si128(destination.add(i + 3), temp3);
+            _mm_stream_si128(destination.add(i + 4), temp4);
+            _mm_stream_si128(destination.add(i + 5), temp5);
+            _mm_stream_si128(destination.add(i + 6), temp6);
+            _mm_stream_si128(destination.add(i + 7), temp7);
+        }
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn memcpy_intrinsics_256_nt_pl_pf(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const __m256i;
+    let destination = destination as *mut __m256i;
+    for i in (0..size / 32).step_by(8) {
+        unsafe {
+            prefetch::<_MM_HINT_NTA>(source.wrapping_add(i + 8) as *const u8, 256);
+            let temp0 = _mm256_load_si256(source.add(i));
+            let temp1 = _mm256_load_si256(source.add(i + 1));
+            let temp2 = _mm256_load_si256(source.add(i + 2));
+            let temp3 = _mm256_load_si256(source.add(i + 3));
+            let temp4 = _mm256_load_si256(source.add(i + 4));
+            let temp5 = _mm256_load_si256(source.add(i + 5));
+            let temp6 = _mm256_load_si256(source.add(i + 6));
+            let temp7 = _mm256_load_si256(source.add(i + 7));
+            _mm256_stream_si256(destination.add(i), temp0);
+            _mm256_stream_si256(destination.add(i + 1), temp1);
+            _mm256_stream_si256(destination.add(i + 2), temp2);
+            _mm256_stream_si256(destination.add(i + 3), temp3);
+            _mm256_stream_si256(destination.add(i + 4), temp4);
+            _mm256_stream_si256(destination.add(i + 5), temp5);
+            _mm256_stream_si256(destination.add(i + 6), temp6);
+            _mm256_stream_si256(destination.add(i + 7), temp7);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::dispatch;
+use std::cell::Cell;
+use std::os::raw::{c_int, c_void};
+use std::process;
+
+// Strong definitions of the libc functions the compiler calls, which take precedence over libc in
+// any executable linking the crate with the `override` feature. They follow the libc argument
+// order, the reverse of the kernels.
+
+#[thread_local]
+static ACTIVE: Cell<bool> = Cell::new(false);
+
+/// Aborts if one of the overrides is reentered, which means that something under it calls back into
+/// `memcpy` and would otherwise recurse until the stack overflows. Release builds are the ones where
+/// the compiler is most likely to turn a loop into such a call, so the check is made in all builds
+/// at the cost of a thread-local load and store. A signal handler copying memory while a copy is
+/// interrupted also reenters and aborts.
+struct Guard;
+
+impl Guard {
+    fn enter() -> Guard {
+        if ACTIVE.replace(true) {
+            process::abort();
+        }
+        Guard
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        ACTIVE.set(false);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn memcpy(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    let _guard = Guard::enter();
+    dispatch::memcpy(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+#[no_mangle]
+pub extern "C" fn memmove(
+    destination: *mut c_void,
+    source: *const c_void,
+    size: usize,
+) -> *mut c_void {
+    let _guard = Guard::enter();
+    dispatch::memmove(size, source as *mut u8, destination as *mut u8);
+    destination
+}
+
+#[no_mangle]
+pub extern "C" fn memset(destination: *mut c_void, value: c_int, size: usize) -> *mut c_void {
+    let _guard = Guard::enter();
+    dispatch::memset(size, value as u8, destination as *mut u8);
+    destination
+}
+
+#[no_mangle]
+pub extern "C" fn memcmp(left: *const c_void, right: *const c_void, size: usize) -> c_int {
+    let _guard = Guard::enter();
+    dispatch::memcmp(size, left as *const u8, right as *const u8)
+}
+
+// LLVM calls `bcmp` rather than `memcmp` when only equality matters, as for slice comparisons.
+#[no_mangle]
+pub extern "C" fn bcmp(left: *const c_void, right: *const c_void, size: usize) -> c_int {
+    memcmp(left, right, size)
+}
@@ -0,0 +1,144 @@
+// The kernels keep the signatures of the assembly ones, which are not `unsafe` either.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use core::ptr;
+use core::sync::atomic::{self, Ordering};
+
+// Plain Rust versions of the kernels, under the same names, for the targets the assembly does not
+// run on and for Miri. Each one copies the same words as the kernel it stands for, with unaligned
+// accesses since many kernels accept any alignment, but without the pipelining or the non-temporal
+// stores, which have no portable equivalent. The words are arrays of `u64` rather than SIMD types
+// so that the layout does not depend on the target.
+
+/// Copies `size / size_of::<T>()` words of type `T`, forward.
+fn copy<T>(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const T;
+    let destination = destination as *mut T;
+    for i in 0..size / core::mem::size_of::<T>() {
+        unsafe {
+            ptr::write_unaligned(destination.add(i), ptr::read_unaligned(source.add(i)));
+        }
+    }
+}
+
+macro_rules! kernels {
+    ($($name:ident: $word:ty,)*) => {
+        $(
+            pub fn $name(size: usize, source: *mut u8, destination: *mut u8) {
+                copy::<$word>(size, source, destination);
+            }
+        )*
+    };
+}
+
+kernels! {
+    memcpy_loop_64: u64,
+    memcpy_loop_256: [u64; 4],
+    memcpy_mov_32: u32,
+    memcpy_mov_64: u64,
+    memcpy_mov_128: [u64; 2],
+    memcpy_mov_256: [u64; 4],
+    memcpy_mov_64_pl: u64,
+    memcpy_mov_128_pl: [u64; 2],
+    memcpy_mov_256_pl: [u64; 4],
+    memcpy_mov_64_nt: u64,
+    memcpy_mov_128_nt: [u64; 2],
+    memcpy_mov_256_nt: [u64; 4],
+    memcpy_mov_64_nt_pl: u64,
+    memcpy_mov_128_nt_pl: [u64; 2],
+    memcpy_mov_256_nt_pl: [u64; 4],
+    memcpy_movu_128_nt_pl: [u64; 2],
+    memcpy_movu_256_nt_pl: [u64; 4],
+    memcpy_intrinsics_32: u32,
+    memcpy_intrinsics_64: u64,
+    memcpy_intrinsics_128: [u64; 2],
+    memcpy_intrinsics_256: [u64; 4],
+    memcpy_intrinsics_64_pl: u64,
+    memcpy_intrinsics_128_pl: [u64; 2],
+    memcpy_intrinsics_256_pl: [u64; 4],
+    memcpy_intrinsics_64_nt: u64,
+    memcpy_intrinsics_128_nt: [u64; 2],
+    memcpy_intrinsics_256_nt: [u64; 4],
+    memcpy_intrinsics_64_nt_pl: u64,
+    memcpy_intrinsics_128_nt_pl: [u64; 2],
+    memcpy_intrinsics_256_nt_pl: [u64; 4],
+    memcpy_intrinsics_64_pl_pf: u64,
+    memcpy_intrinsics_128_pl_pf: [u64; 2],
+    memcpy_intrinsics_256_pl_pf: [u64; 4],
+    memcpy_intrinsics_64_nt_pl_pf: u64,
+    memcpy_intrinsics_128_nt_pl_pf: [u64; 2],
+    memcpy_intrinsics_256_nt_pl_pf: [u64; 4],
+    memcpy_rep_movsb: u8,
+    memcpy_rep_movsq: u64,
+}
+
+// Copies from the last byte down, which is what `memmove` needs when the destination overlaps the
+// end of the source.
+pub fn memmove_rep_movsb(size: usize, source: *mut u8, destination: *mut u8) {
+    for i in (0..size).rev() {
+        unsafe { *destination.add(i) = *source.add(i) };
+    }
+}
+
+pub fn memset_rep_stosb(size: usize, value: u8, destination: *mut u8) {
+    for i in 0..size {
+        unsafe { *destination.add(i) = value };
+    }
+}
+
+pub fn memcmp_mov_64(size: usize, left: *const u8, right: *const u8) -> i32 {
+    for i in 0..size {
+        let (a, b) = unsafe { (*left.add(i), *right.add(i)) };
+        if a != b {
+            return if a < b { -1 } else { 1 };
+        }
+    }
+    0
+}
+
+/// Copies `size / size_of::<T>()` elements of type `T`, reversing the bytes of each.
+fn copy_bswap<T>(size: usize, source: *mut u8, destination: *mut u8, swap: fn(T) -> T) {
+    let source = source as *const T;
+    let destination = destination as *mut T;
+    for i in 0..size / core::mem::size_of::<T>() {
+        unsafe {
+            let element = ptr::read_unaligned(source.add(i));
+            ptr::write_unaligned(destination.add(i), swap(element));
+        }
+    }
+}
+
+macro_rules! bswap_kernels {
+    ($($word:ty: $($name:ident),*;)*) => {
+        $($(
+            pub fn $name(size: usize, source: *mut u8, destination: *mut u8) {
+                copy_bswap::<$word>(size, source, destination, <$word>::swap_bytes);
+            }
+        )*)*
+    };
+}
+
+bswap_kernels! {
+    u16: copy_bswap16_movbe, copy_bswap16_movbe_pl, copy_bswap16_movbe_nt,
+        copy_bswap16_movbe_nt_pl, copy_bswap16_128, copy_bswap16_128_pl, copy_bswap16_128_nt,
+        copy_bswap16_128_nt_pl, copy_bswap16_256, copy_bswap16_256_pl, copy_bswap16_256_nt,
+        copy_bswap16_256_nt_pl;
+    u32: copy_bswap32_movbe, copy_bswap32_movbe_pl, copy_bswap32_movbe_nt,
+        copy_bswap32_movbe_nt_pl, copy_bswap32_128, copy_bswap32_128_pl, copy_bswap32_128_nt,
+        copy_bswap32_128_nt_pl, copy_bswap32_256, copy_bswap32_256_pl, copy_bswap32_256_nt,
+        copy_bswap32_256_nt_pl;
+    u64: copy_bswap64_movbe, copy_bswap64_movbe_pl, copy_bswap64_movbe_nt,
+        copy_bswap64_movbe_nt_pl, copy_bswap64_128, copy_bswap64_128_pl, copy_bswap64_128_nt,
+        copy_bswap64_128_nt_pl, copy_bswap64_256, copy_bswap64_256_pl, copy_bswap64_256_nt,
+        copy_bswap64_256_nt_pl;
+}
+
+/// Without non-temporal stores, ordering the copy before what follows is all that is left to do.
+pub(crate) fn sfence() {
+    atomic::fence(Ordering::Release);
+}
+
+/// The portable kernels only need what the target has.
+pub(crate) fn is_detected(_feature: &str) -> bool {
+    true
+}
@@ -0,0 +1,1307 @@
+use crate::backend;
+use crate::*;
+
+pub struct Kernel {
+    pub name: &'static str,
+    pub copy: fn(usize, *mut u8, *mut u8),
+    /// The source and the destination must be aligned on this many bytes, a power of two.
+    pub alignment: usize,
+    /// The size must be a nonzero multiple of this many bytes, a power of two.
+    pub granularity: usize,
+    /// The instruction set extensions the kernel needs beyond x86-64, as `is_x86_feature_detected!`
+    /// names them.
+    pub features: &'static [&'static str],
+}
+
+impl Kernel {
+    pub fn accepts(&self, size: usize, source: *const u8, destination: *const u8) -> bool {
+        size != 0
+            && size & (self.granularity - 1) == 0
+            && (source as usize | destination as usize) & (self.alignment - 1) == 0
+    }
+
+    /// Whether the processor running the program has the features of the kernel.
+    pub fn is_supported(&self) -> bool {
+        self.features
+            .iter()
+            .all(|feature| backend::is_detected(feature))
+    }
+}
+
+/// The copy kernels, under the names of their functions without the `memcpy_` prefix.
+pub const KERNELS: &[Kernel] = &[
+    Kernel {
+        name: "loop_64",
+        copy: memcpy_loop_64,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "loop_256",
+        copy: memcpy_loop_256,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "mov_32",
+        copy: memcpy_mov_32,
+        alignment: 1,
+        granularity: 4,
+        features: &[],
+    },
+    Kernel {
+        name: "mov_64",
+        copy: memcpy_mov_64,
+        alignment: 1,
+        granularity: 8,
+        features: &[],
+    },
+    Kernel {
+        name: "mov_128",
+        copy: memcpy_mov_128,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "mov_256",
+        copy: memcpy_mov_256,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "mov_64_pl",
+        copy: memcpy_mov_64_pl,
+        alignment: 1,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "mov_128_pl",
+        copy: memcpy_mov_128_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "mov_256_pl",
+        copy: memcpy_mov_256_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "mov_64_nt",
+        copy: memcpy_mov_64_nt,
+        alignment: 1,
+        granularity: 8,
+        features: &[],
+    },
+    Kernel {
+        name: "mov_128_nt",
+        copy: memcpy_mov_128_nt,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "mov_256_nt",
+        copy: memcpy_mov_256_nt,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "mov_64_nt_pl",
+        copy: memcpy_mov_64_nt_pl,
+        alignment: 1,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "mov_128_nt_pl",
+        copy: memcpy_mov_128_nt_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "mov_256_nt_pl",
+        copy: memcpy_mov_256_nt_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "movu_128_nt_pl",
+        copy: memcpy_movu_128_nt_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "movu_256_nt_pl",
+        copy: memcpy_movu_256_nt_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "intrinsics_32",
+        copy: memcpy_intrinsics_32,
+        alignment: 4,
+        granularity: 4,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_64",
+        copy: memcpy_intrinsics_64,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_128",
+        copy: memcpy_intrinsics_128,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "intrinsics_256",
+        copy: memcpy_intrinsics_256,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "intrinsics_64_pl",
+        copy: memcpy_intrinsics_64_pl,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_128_pl",
+        copy: memcpy_intrinsics_128_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "intrinsics_256_pl",
+        copy: memcpy_intrinsics_256_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "intrinsics_64_nt",
+        copy: memcpy_intrinsics_64_nt,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_128_nt",
+        copy: memcpy_intrinsics_128_nt,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "intrinsics_256_nt",
+        copy: memcpy_intrinsics_256_nt,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "intrinsics_64_nt_pl",
+        copy: memcpy_intrinsics_64_nt_pl,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_128_nt_pl",
+        copy: memcpy_intrinsics_128_nt_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "intrinsics_256_nt_pl",
+        copy: memcpy_intrinsics_256_nt_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "intrinsics_64_pl_pf",
+        copy: memcpy_intrinsics_64_pl_pf,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_128_pl_pf",
+        copy: memcpy_intrinsics_128_pl_pf,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "intrinsics_256_pl_pf",
+        copy: memcpy_intrinsics_256_pl_pf,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "intrinsics_64_nt_pl_pf",
+        copy: memcpy_intrinsics_64_nt_pl_pf,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "intrinsics_128_nt_pl_pf",
+        copy: memcpy_intrinsics_128_nt_pl_pf,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Kernel {
+        name: "intrinsics_256_nt_pl_pf",
+        copy: memcpy_intrinsics_256_nt_pl_pf,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Kernel {
+        name: "simd_128",
+        copy: memcpy_simd_128,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    Kernel {
+        name: "simd_128_pl",
+        copy: memcpy_simd_128_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    Kernel {
+        name: "simd_256",
+        copy: memcpy_simd_256,
+        alignment: 32,
+        granularity: 32,
+        features: &[],
+    },
+    Kernel {
+        name: "simd_256_pl",
+        copy: memcpy_simd_256_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &[],
+    },
+    Kernel {
+        name: "simd_512",
+        copy: memcpy_simd_512,
+        alignment: 64,
+        granularity: 64,
+        features: &[],
+    },
+    Kernel {
+        name: "simd_512_pl",
+        copy: memcpy_simd_512_pl,
+        alignment: 64,
+        granularity: 512,
+        features: &[],
+    },
+    Kernel {
+        name: "rep_movsb",
+        copy: memcpy_rep_movsb,
+        alignment: 1,
+        granularity: 1,
+        features: &[],
+    },
+    Kernel {
+        name: "rep_movsq",
+        copy: memcpy_rep_movsq,
+        alignment: 1,
+        granularity: 8,
+        features: &[],
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Kernel> {
+    KERNELS.iter().find(|kernel| kernel.name == name)
+}
+
+/// The kernels that reverse the bytes of each element of 16, 32 or 64 bits as they copy, under the
+/// names of their functions without the `copy_` prefix.
+pub const SWAPS: &[Kernel] = &[
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap16_movbe",
+        copy: copy_bswap16_movbe,
+        alignment: 1,
+        granularity: 2,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap32_movbe",
+        copy: copy_bswap32_movbe,
+        alignment: 1,
+        granularity: 4,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap64_movbe",
+        copy: copy_bswap64_movbe,
+        alignment: 1,
+        granularity: 8,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap16_movbe_pl",
+        copy: copy_bswap16_movbe_pl,
+        alignment: 1,
+        granularity: 32,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap32_movbe_pl",
+        copy: copy_bswap32_movbe_pl,
+        alignment: 1,
+        granularity: 32,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap64_movbe_pl",
+        copy: copy_bswap64_movbe_pl,
+        alignment: 1,
+        granularity: 64,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap16_movbe_nt",
+        copy: copy_bswap16_movbe_nt,
+        alignment: 1,
+        granularity: 2,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap32_movbe_nt",
+        copy: copy_bswap32_movbe_nt,
+        alignment: 1,
+        granularity: 4,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap64_movbe_nt",
+        copy: copy_bswap64_movbe_nt,
+        alignment: 1,
+        granularity: 8,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap16_movbe_nt_pl",
+        copy: copy_bswap16_movbe_nt_pl,
+        alignment: 1,
+        granularity: 32,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap32_movbe_nt_pl",
+        copy: copy_bswap32_movbe_nt_pl,
+        alignment: 1,
+        granularity: 32,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "movbe")]
+    Kernel {
+        name: "bswap64_movbe_nt_pl",
+        copy: copy_bswap64_movbe_nt_pl,
+        alignment: 1,
+        granularity: 64,
+        features: &["movbe"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap16_128",
+        copy: copy_bswap16_128,
+        alignment: 16,
+        granularity: 16,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap32_128",
+        copy: copy_bswap32_128,
+        alignment: 16,
+        granularity: 16,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap64_128",
+        copy: copy_bswap64_128,
+        alignment: 16,
+        granularity: 16,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap16_128_pl",
+        copy: copy_bswap16_128_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap32_128_pl",
+        copy: copy_bswap32_128_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap64_128_pl",
+        copy: copy_bswap64_128_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap16_128_nt",
+        copy: copy_bswap16_128_nt,
+        alignment: 16,
+        granularity: 16,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap32_128_nt",
+        copy: copy_bswap32_128_nt,
+        alignment: 16,
+        granularity: 16,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap64_128_nt",
+        copy: copy_bswap64_128_nt,
+        alignment: 16,
+        granularity: 16,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap16_128_nt_pl",
+        copy: copy_bswap16_128_nt_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap32_128_nt_pl",
+        copy: copy_bswap32_128_nt_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "ssse3")]
+    Kernel {
+        name: "bswap64_128_nt_pl",
+        copy: copy_bswap64_128_nt_pl,
+        alignment: 16,
+        granularity: 128,
+        features: &["ssse3"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap16_256",
+        copy: copy_bswap16_256,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap32_256",
+        copy: copy_bswap32_256,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap64_256",
+        copy: copy_bswap64_256,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap16_256_pl",
+        copy: copy_bswap16_256_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap32_256_pl",
+        copy: copy_bswap32_256_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap64_256_pl",
+        copy: copy_bswap64_256_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap16_256_nt",
+        copy: copy_bswap16_256_nt,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap32_256_nt",
+        copy: copy_bswap32_256_nt,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap64_256_nt",
+        copy: copy_bswap64_256_nt,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap16_256_nt_pl",
+        copy: copy_bswap16_256_nt_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap32_256_nt_pl",
+        copy: copy_bswap32_256_nt_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx2"],
+    },
+    #[cfg(target_feature = "avx2")]
+    Kernel {
+        name: "bswap64_256_nt_pl",
+        copy: copy_bswap64_256_nt_pl,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx2"],
+    },
+];
+
+/// A kernel of the STREAM benchmark, over three arrays of `f64` that STREAM calls a, b and c.
+pub struct Stream {
+    pub name: &'static str,
+    pub run: fn(usize, *mut u8, *mut u8, *mut u8),
+    /// How many of the arrays the kernel reads, and how many it writes, to count the bytes it
+    /// moves as STREAM does.
+    pub reads: usize,
+    pub writes: usize,
+    /// The arrays must be aligned on this many bytes, a power of two.
+    pub alignment: usize,
+    /// The size of each array must be a nonzero multiple of this many bytes, a power of two.
+    pub granularity: usize,
+    /// The instruction set extensions the kernel needs beyond x86-64, as `is_x86_feature_detected!`
+    /// names them.
+    pub features: &'static [&'static str],
+}
+
+impl Stream {
+    pub fn accepts(&self, size: usize, a: *const u8, b: *const u8, c: *const u8) -> bool {
+        size != 0
+            && size & (self.granularity - 1) == 0
+            && (a as usize | b as usize | c as usize) & (self.alignment - 1) == 0
+    }
+
+    /// Whether the processor running the program has the features of the kernel.
+    pub fn is_supported(&self) -> bool {
+        self.features
+            .iter()
+            .all(|feature| backend::is_detected(feature))
+    }
+}
+
+/// The STREAM kernels, under the names of their functions without the `stream_` prefix. The
+/// portable backend has none.
+#[cfg(not(any(feature = "portable", miri, not(target_arch = "x86_64"))))]
+pub const STREAMS: &[Stream] = &[
+    Stream {
+        name: "read_64",
+        run: stream_read_64,
+        reads: 1,
+        writes: 0,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "read_128",
+        run: stream_read_128,
+        reads: 1,
+        writes: 0,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "read_256",
+        run: stream_read_256,
+        reads: 1,
+        writes: 0,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "read_64_pl",
+        run: stream_read_64_pl,
+        reads: 1,
+        writes: 0,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "read_128_pl",
+        run: stream_read_128_pl,
+        reads: 1,
+        writes: 0,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "read_256_pl",
+        run: stream_read_256_pl,
+        reads: 1,
+        writes: 0,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "write_64",
+        run: stream_write_64,
+        reads: 0,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "write_128",
+        run: stream_write_128,
+        reads: 0,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "write_256",
+        run: stream_write_256,
+        reads: 0,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "write_64_pl",
+        run: stream_write_64_pl,
+        reads: 0,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "write_128_pl",
+        run: stream_write_128_pl,
+        reads: 0,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "write_256_pl",
+        run: stream_write_256_pl,
+        reads: 0,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "write_64_nt",
+        run: stream_write_64_nt,
+        reads: 0,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "write_128_nt",
+        run: stream_write_128_nt,
+        reads: 0,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "write_256_nt",
+        run: stream_write_256_nt,
+        reads: 0,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "write_64_nt_pl",
+        run: stream_write_64_nt_pl,
+        reads: 0,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "write_128_nt_pl",
+        run: stream_write_128_nt_pl,
+        reads: 0,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "write_256_nt_pl",
+        run: stream_write_256_nt_pl,
+        reads: 0,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "copy_64",
+        run: stream_copy_64,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "copy_128",
+        run: stream_copy_128,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "copy_256",
+        run: stream_copy_256,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "copy_64_pl",
+        run: stream_copy_64_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "copy_128_pl",
+        run: stream_copy_128_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "copy_256_pl",
+        run: stream_copy_256_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "copy_64_nt",
+        run: stream_copy_64_nt,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "copy_128_nt",
+        run: stream_copy_128_nt,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "copy_256_nt",
+        run: stream_copy_256_nt,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "copy_64_nt_pl",
+        run: stream_copy_64_nt_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "copy_128_nt_pl",
+        run: stream_copy_128_nt_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "copy_256_nt_pl",
+        run: stream_copy_256_nt_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "scale_64",
+        run: stream_scale_64,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "scale_128",
+        run: stream_scale_128,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "scale_256",
+        run: stream_scale_256,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "scale_64_pl",
+        run: stream_scale_64_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "scale_128_pl",
+        run: stream_scale_128_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "scale_256_pl",
+        run: stream_scale_256_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "scale_64_nt",
+        run: stream_scale_64_nt,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "scale_128_nt",
+        run: stream_scale_128_nt,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "scale_256_nt",
+        run: stream_scale_256_nt,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "scale_64_nt_pl",
+        run: stream_scale_64_nt_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "scale_128_nt_pl",
+        run: stream_scale_128_nt_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "scale_256_nt_pl",
+        run: stream_scale_256_nt_pl,
+        reads: 1,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "add_64",
+        run: stream_add_64,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "add_128",
+        run: stream_add_128,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "add_256",
+        run: stream_add_256,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "add_64_pl",
+        run: stream_add_64_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "add_128_pl",
+        run: stream_add_128_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "add_256_pl",
+        run: stream_add_256_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "add_64_nt",
+        run: stream_add_64_nt,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "add_128_nt",
+        run: stream_add_128_nt,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "add_256_nt",
+        run: stream_add_256_nt,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "add_64_nt_pl",
+        run: stream_add_64_nt_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "add_128_nt_pl",
+        run: stream_add_128_nt_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "add_256_nt_pl",
+        run: stream_add_256_nt_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "triad_64",
+        run: stream_triad_64,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "triad_128",
+        run: stream_triad_128,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "triad_256",
+        run: stream_triad_256,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "triad_64_pl",
+        run: stream_triad_64_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "triad_128_pl",
+        run: stream_triad_128_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "triad_256_pl",
+        run: stream_triad_256_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+    Stream {
+        name: "triad_64_nt",
+        run: stream_triad_64_nt,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 8,
+        features: &[],
+    },
+    Stream {
+        name: "triad_128_nt",
+        run: stream_triad_128_nt,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 16,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "triad_256_nt",
+        run: stream_triad_256_nt,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 32,
+        features: &["avx"],
+    },
+    Stream {
+        name: "triad_64_nt_pl",
+        run: stream_triad_64_nt_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 8,
+        granularity: 64,
+        features: &[],
+    },
+    Stream {
+        name: "triad_128_nt_pl",
+        run: stream_triad_128_nt_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 16,
+        granularity: 128,
+        features: &[],
+    },
+    #[cfg(target_feature = "avx")]
+    Stream {
+        name: "triad_256_nt_pl",
+        run: stream_triad_256_nt_pl,
+        reads: 2,
+        writes: 1,
+        alignment: 32,
+        granularity: 256,
+        features: &["avx"],
+    },
+];
+
+#[cfg(any(feature = "portable", miri, not(target_arch = "x86_64")))]
+pub const STREAMS: &[Stream] = &[];
@@ -0,0 +1,63 @@
+use core::simd::Simd;
+
+// Kernels written once with portable vectors of `N` bytes, which the compiler lowers to the vector
+// instructions of the target, splitting a vector wider than its registers. They stand next to the
+// `mov` kernels of the same width to show what the abstraction costs: plain loops copying a vector
+// per iteration, and pipelined ones loading eight vectors before storing them.
+
+fn copy<const N: usize>(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const Simd<u8, N>;
+    let destination = destination as *mut Simd<u8, N>;
+    for i in 0..size / N {
+        unsafe { *destination.add(i) = *source.add(i) };
+    }
+}
+
+fn copy_pl<const N: usize>(size: usize, source: *mut u8, destination: *mut u8) {
+    let source = source as *const Simd<u8, N>;
+    let destination = destination as *mut Simd<u8, N>;
+    for i in (0..size / N).step_by(8) {
+        unsafe {
+            let temp0 = *source.add(i);
+            let temp1 = *source.add(i + 1);
+            let temp2 = *source.add(i + 2);
+            let temp3 = *source.add(i + 3);
+            let temp4 = *source.add(i + 4);
+            let temp5 = *source.add(i + 5);
+            let temp6 = *source.add(i + 6);
+            let temp7 = *source.add(i + 7);
+            *destination.add(i) = temp0;
+            *destination.add(i + 1) = temp1;
+            *destination.add(i + 2) = temp2;
+            *destination.add(i + 3) = temp3;
+            *destination.add(i + 4) = temp4;
+            *destination.add(i + 5) = temp5;
+            *destination.add(i + 6) = temp6;
+            *destination.add(i + 7) = temp7;
+        }
+    }
+}
+
+pub fn memcpy_simd_128(size: usize, source: *mut u8, destination: *mut u8) {
+    copy::<16>(size, source, destination);
+}
+
+pub fn memcpy_simd_256(size: usize, source: *mut u8, destination: *mut u8) {
+    copy::<32>(size, source, destination);
+}
+
+pub fn memcpy_simd_512(size: usize, source: *mut u8, destination: *mut u8) {
+    copy::<64>(size, source, destination);
+}
+
+pub fn memcpy_simd_128_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pl::<16>(size, source, destination);
+}
+
+pub fn memcpy_simd_256_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pl::<32>(size, source, destination);
+}
+
+pub fn memcpy_simd_512_pl(size: usize, source: *mut u8, destination: *mut u8) {
+    copy_pl::<64>(size, source, destination);
+}
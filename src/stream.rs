@@ -0,0 +1,1820 @@
+use crate::{
+    memcpy_mov_128, memcpy_mov_128_nt, memcpy_mov_128_nt_pl, memcpy_mov_128_pl, memcpy_mov_64,
+    memcpy_mov_64_nt, memcpy_mov_64_nt_pl, memcpy_mov_64_pl,
+};
+#[cfg(target_feature = "avx")]
+use crate::{memcpy_mov_256, memcpy_mov_256_nt, memcpy_mov_256_nt_pl, memcpy_mov_256_pl};
+use core::arch::asm;
+#[cfg(target_feature = "avx")]
+use core::arch::x86_64::_mm256_set1_pd;
+use core::arch::x86_64::_mm_set1_pd;
+
+// The kernels of the STREAM benchmark over arrays of `f64`, in the widths and forms of the
+// `memcpy_mov_*` kernels, to read the bandwidth of the copies against that of the memory:
+//
+//     read   reads a
+//     write  a = q
+//     copy   c = a
+//     scale  b = q * c
+//     add    c = a + b
+//     triad  a = b + q * c
+//
+// STREAM itself has no `read` or `write`. Every kernel takes the three arrays, of `size` bytes
+// each, whether it uses them or not. The 64-bit kernels work on scalars and the 128-bit ones on
+// pairs with SSE2, so only the 256-bit ones need AVX. The 64-bit non-temporal kernels store
+// through general purpose registers with `movnti`. Reading has no non-temporal form.
+
+/// The scalar `q` of writing, scaling and the triad, as in STREAM.
+pub const SCALAR: f64 = 3.0;
+
+pub fn stream_copy_64(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_64(size, a, c)
+}
+
+pub fn stream_copy_128(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_128(size, a, c)
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_copy_256(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_256(size, a, c)
+}
+
+pub fn stream_copy_64_pl(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_64_pl(size, a, c)
+}
+
+pub fn stream_copy_128_pl(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_128_pl(size, a, c)
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_copy_256_pl(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_256_pl(size, a, c)
+}
+
+pub fn stream_copy_64_nt(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_64_nt(size, a, c)
+}
+
+pub fn stream_copy_128_nt(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_128_nt(size, a, c)
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_copy_256_nt(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_256_nt(size, a, c)
+}
+
+pub fn stream_copy_64_nt_pl(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_64_nt_pl(size, a, c)
+}
+
+pub fn stream_copy_128_nt_pl(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_128_nt_pl(size, a, c)
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_copy_256_nt_pl(size: usize, a: *mut u8, _b: *mut u8, c: *mut u8) {
+    memcpy_mov_256_nt_pl(size, a, c)
+}
+
+pub fn stream_read_64(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{a} + {counter:r} * 8]",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_read_128(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{a} + {counter:r}]",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_read_256(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd {temp}, [{a} + {counter:r}]",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_read_64_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{a} + {counter:r}]",
+            "    movsd xmm1, [{a} + {counter:r} + 8]",
+            "    movsd xmm2, [{a} + {counter:r} + 16]",
+            "    movsd xmm3, [{a} + {counter:r} + 24]",
+            "    movsd xmm4, [{a} + {counter:r} + 32]",
+            "    movsd xmm5, [{a} + {counter:r} + 40]",
+            "    movsd xmm6, [{a} + {counter:r} + 48]",
+            "    movsd xmm7, [{a} + {counter:r} + 56]",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_read_128_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{a} + {counter:r}]",
+            "    movapd xmm1, [{a} + {counter:r} + 16]",
+            "    movapd xmm2, [{a} + {counter:r} + 32]",
+            "    movapd xmm3, [{a} + {counter:r} + 48]",
+            "    movapd xmm4, [{a} + {counter:r} + 64]",
+            "    movapd xmm5, [{a} + {counter:r} + 80]",
+            "    movapd xmm6, [{a} + {counter:r} + 96]",
+            "    movapd xmm7, [{a} + {counter:r} + 112]",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_read_256_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd ymm0, [{a} + {counter:r}]",
+            "    vmovapd ymm1, [{a} + {counter:r} + 32]",
+            "    vmovapd ymm2, [{a} + {counter:r} + 64]",
+            "    vmovapd ymm3, [{a} + {counter:r} + 96]",
+            "    vmovapd ymm4, [{a} + {counter:r} + 128]",
+            "    vmovapd ymm5, [{a} + {counter:r} + 160]",
+            "    vmovapd ymm6, [{a} + {counter:r} + 192]",
+            "    vmovapd ymm7, [{a} + {counter:r} + 224]",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_64(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd [{a} + {counter:r} * 8], {q}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            q = in(xmm_reg) SCALAR,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_128(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd [{a} + {counter:r}], {q}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_write_256(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd [{a} + {counter:r}], {q}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_64_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd [{a} + {counter:r}], {q}",
+            "    movsd [{a} + {counter:r} + 8], {q}",
+            "    movsd [{a} + {counter:r} + 16], {q}",
+            "    movsd [{a} + {counter:r} + 24], {q}",
+            "    movsd [{a} + {counter:r} + 32], {q}",
+            "    movsd [{a} + {counter:r} + 40], {q}",
+            "    movsd [{a} + {counter:r} + 48], {q}",
+            "    movsd [{a} + {counter:r} + 56], {q}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) SCALAR,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_128_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd [{a} + {counter:r}], {q}",
+            "    movapd [{a} + {counter:r} + 16], {q}",
+            "    movapd [{a} + {counter:r} + 32], {q}",
+            "    movapd [{a} + {counter:r} + 48], {q}",
+            "    movapd [{a} + {counter:r} + 64], {q}",
+            "    movapd [{a} + {counter:r} + 80], {q}",
+            "    movapd [{a} + {counter:r} + 96], {q}",
+            "    movapd [{a} + {counter:r} + 112], {q}",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_write_256_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd [{a} + {counter:r}], {q}",
+            "    vmovapd [{a} + {counter:r} + 32], {q}",
+            "    vmovapd [{a} + {counter:r} + 64], {q}",
+            "    vmovapd [{a} + {counter:r} + 96], {q}",
+            "    vmovapd [{a} + {counter:r} + 128], {q}",
+            "    vmovapd [{a} + {counter:r} + 160], {q}",
+            "    vmovapd [{a} + {counter:r} + 192], {q}",
+            "    vmovapd [{a} + {counter:r} + 224], {q}",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_64_nt(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movnti [{a} + {counter:r} * 8], {q:r}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            q = in(reg) SCALAR.to_bits(),
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_128_nt(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movntpd [{a} + {counter:r}], {q}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_write_256_nt(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovntpd [{a} + {counter:r}], {q}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_64_nt_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movnti [{a} + {counter:r}], {q:r}",
+            "    movnti [{a} + {counter:r} + 8], {q:r}",
+            "    movnti [{a} + {counter:r} + 16], {q:r}",
+            "    movnti [{a} + {counter:r} + 24], {q:r}",
+            "    movnti [{a} + {counter:r} + 32], {q:r}",
+            "    movnti [{a} + {counter:r} + 40], {q:r}",
+            "    movnti [{a} + {counter:r} + 48], {q:r}",
+            "    movnti [{a} + {counter:r} + 56], {q:r}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(reg) SCALAR.to_bits(),
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_write_128_nt_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movntpd [{a} + {counter:r}], {q}",
+            "    movntpd [{a} + {counter:r} + 16], {q}",
+            "    movntpd [{a} + {counter:r} + 32], {q}",
+            "    movntpd [{a} + {counter:r} + 48], {q}",
+            "    movntpd [{a} + {counter:r} + 64], {q}",
+            "    movntpd [{a} + {counter:r} + 80], {q}",
+            "    movntpd [{a} + {counter:r} + 96], {q}",
+            "    movntpd [{a} + {counter:r} + 112], {q}",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_write_256_nt_pl(size: usize, a: *mut u8, _b: *mut u8, _c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovntpd [{a} + {counter:r}], {q}",
+            "    vmovntpd [{a} + {counter:r} + 32], {q}",
+            "    vmovntpd [{a} + {counter:r} + 64], {q}",
+            "    vmovntpd [{a} + {counter:r} + 96], {q}",
+            "    vmovntpd [{a} + {counter:r} + 128], {q}",
+            "    vmovntpd [{a} + {counter:r} + 160], {q}",
+            "    vmovntpd [{a} + {counter:r} + 192], {q}",
+            "    vmovntpd [{a} + {counter:r} + 224], {q}",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_64(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{c} + {counter:r} * 8]",
+            "    mulsd {temp}, {q}",
+            "    movsd [{b} + {counter:r} * 8], {temp}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            q = in(xmm_reg) SCALAR,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_128(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{c} + {counter:r}]",
+            "    mulpd {temp}, {q}",
+            "    movapd [{b} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_scale_256(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd {temp}, {q}, [{c} + {counter:r}]",
+            "    vmovapd [{b} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_64_pl(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{c} + {counter:r}]",
+            "    mulsd xmm0, {q}",
+            "    movsd xmm1, [{c} + {counter:r} + 8]",
+            "    mulsd xmm1, {q}",
+            "    movsd xmm2, [{c} + {counter:r} + 16]",
+            "    mulsd xmm2, {q}",
+            "    movsd xmm3, [{c} + {counter:r} + 24]",
+            "    mulsd xmm3, {q}",
+            "    movsd xmm4, [{c} + {counter:r} + 32]",
+            "    mulsd xmm4, {q}",
+            "    movsd xmm5, [{c} + {counter:r} + 40]",
+            "    mulsd xmm5, {q}",
+            "    movsd xmm6, [{c} + {counter:r} + 48]",
+            "    mulsd xmm6, {q}",
+            "    movsd xmm7, [{c} + {counter:r} + 56]",
+            "    mulsd xmm7, {q}",
+            "    movsd [{b} + {counter:r}], xmm0",
+            "    movsd [{b} + {counter:r} + 8], xmm1",
+            "    movsd [{b} + {counter:r} + 16], xmm2",
+            "    movsd [{b} + {counter:r} + 24], xmm3",
+            "    movsd [{b} + {counter:r} + 32], xmm4",
+            "    movsd [{b} + {counter:r} + 40], xmm5",
+            "    movsd [{b} + {counter:r} + 48], xmm6",
+            "    movsd [{b} + {counter:r} + 56], xmm7",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) SCALAR,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_128_pl(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{c} + {counter:r}]",
+            "    mulpd xmm0, {q}",
+            "    movapd xmm1, [{c} + {counter:r} + 16]",
+            "    mulpd xmm1, {q}",
+            "    movapd xmm2, [{c} + {counter:r} + 32]",
+            "    mulpd xmm2, {q}",
+            "    movapd xmm3, [{c} + {counter:r} + 48]",
+            "    mulpd xmm3, {q}",
+            "    movapd xmm4, [{c} + {counter:r} + 64]",
+            "    mulpd xmm4, {q}",
+            "    movapd xmm5, [{c} + {counter:r} + 80]",
+            "    mulpd xmm5, {q}",
+            "    movapd xmm6, [{c} + {counter:r} + 96]",
+            "    mulpd xmm6, {q}",
+            "    movapd xmm7, [{c} + {counter:r} + 112]",
+            "    mulpd xmm7, {q}",
+            "    movapd [{b} + {counter:r}], xmm0",
+            "    movapd [{b} + {counter:r} + 16], xmm1",
+            "    movapd [{b} + {counter:r} + 32], xmm2",
+            "    movapd [{b} + {counter:r} + 48], xmm3",
+            "    movapd [{b} + {counter:r} + 64], xmm4",
+            "    movapd [{b} + {counter:r} + 80], xmm5",
+            "    movapd [{b} + {counter:r} + 96], xmm6",
+            "    movapd [{b} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_scale_256_pl(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd ymm0, {q}, [{c} + {counter:r}]",
+            "    vmulpd ymm1, {q}, [{c} + {counter:r} + 32]",
+            "    vmulpd ymm2, {q}, [{c} + {counter:r} + 64]",
+            "    vmulpd ymm3, {q}, [{c} + {counter:r} + 96]",
+            "    vmulpd ymm4, {q}, [{c} + {counter:r} + 128]",
+            "    vmulpd ymm5, {q}, [{c} + {counter:r} + 160]",
+            "    vmulpd ymm6, {q}, [{c} + {counter:r} + 192]",
+            "    vmulpd ymm7, {q}, [{c} + {counter:r} + 224]",
+            "    vmovapd [{b} + {counter:r}], ymm0",
+            "    vmovapd [{b} + {counter:r} + 32], ymm1",
+            "    vmovapd [{b} + {counter:r} + 64], ymm2",
+            "    vmovapd [{b} + {counter:r} + 96], ymm3",
+            "    vmovapd [{b} + {counter:r} + 128], ymm4",
+            "    vmovapd [{b} + {counter:r} + 160], ymm5",
+            "    vmovapd [{b} + {counter:r} + 192], ymm6",
+            "    vmovapd [{b} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_64_nt(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{c} + {counter:r} * 8]",
+            "    mulsd {temp}, {q}",
+            "    movq {bits:r}, {temp}",
+            "    movnti [{b} + {counter:r} * 8], {bits:r}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            q = in(xmm_reg) SCALAR,
+            temp = out(xmm_reg) _,
+            bits = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_128_nt(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{c} + {counter:r}]",
+            "    mulpd {temp}, {q}",
+            "    movntpd [{b} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_scale_256_nt(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd {temp}, {q}, [{c} + {counter:r}]",
+            "    vmovntpd [{b} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_64_nt_pl(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{c} + {counter:r}]",
+            "    mulsd xmm0, {q}",
+            "    movsd xmm1, [{c} + {counter:r} + 8]",
+            "    mulsd xmm1, {q}",
+            "    movsd xmm2, [{c} + {counter:r} + 16]",
+            "    mulsd xmm2, {q}",
+            "    movsd xmm3, [{c} + {counter:r} + 24]",
+            "    mulsd xmm3, {q}",
+            "    movq {bits0:r}, xmm0",
+            "    movq {bits1:r}, xmm1",
+            "    movq {bits2:r}, xmm2",
+            "    movq {bits3:r}, xmm3",
+            "    movnti [{b} + {counter:r}], {bits0:r}",
+            "    movnti [{b} + {counter:r} + 8], {bits1:r}",
+            "    movnti [{b} + {counter:r} + 16], {bits2:r}",
+            "    movnti [{b} + {counter:r} + 24], {bits3:r}",
+            "    movsd xmm4, [{c} + {counter:r} + 32]",
+            "    mulsd xmm4, {q}",
+            "    movsd xmm5, [{c} + {counter:r} + 40]",
+            "    mulsd xmm5, {q}",
+            "    movsd xmm6, [{c} + {counter:r} + 48]",
+            "    mulsd xmm6, {q}",
+            "    movsd xmm7, [{c} + {counter:r} + 56]",
+            "    mulsd xmm7, {q}",
+            "    movq {bits0:r}, xmm4",
+            "    movq {bits1:r}, xmm5",
+            "    movq {bits2:r}, xmm6",
+            "    movq {bits3:r}, xmm7",
+            "    movnti [{b} + {counter:r} + 32], {bits0:r}",
+            "    movnti [{b} + {counter:r} + 40], {bits1:r}",
+            "    movnti [{b} + {counter:r} + 48], {bits2:r}",
+            "    movnti [{b} + {counter:r} + 56], {bits3:r}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) SCALAR,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            bits0 = out(reg) _,
+            bits1 = out(reg) _,
+            bits2 = out(reg) _,
+            bits3 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_scale_128_nt_pl(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{c} + {counter:r}]",
+            "    mulpd xmm0, {q}",
+            "    movapd xmm1, [{c} + {counter:r} + 16]",
+            "    mulpd xmm1, {q}",
+            "    movapd xmm2, [{c} + {counter:r} + 32]",
+            "    mulpd xmm2, {q}",
+            "    movapd xmm3, [{c} + {counter:r} + 48]",
+            "    mulpd xmm3, {q}",
+            "    movapd xmm4, [{c} + {counter:r} + 64]",
+            "    mulpd xmm4, {q}",
+            "    movapd xmm5, [{c} + {counter:r} + 80]",
+            "    mulpd xmm5, {q}",
+            "    movapd xmm6, [{c} + {counter:r} + 96]",
+            "    mulpd xmm6, {q}",
+            "    movapd xmm7, [{c} + {counter:r} + 112]",
+            "    mulpd xmm7, {q}",
+            "    movntpd [{b} + {counter:r}], xmm0",
+            "    movntpd [{b} + {counter:r} + 16], xmm1",
+            "    movntpd [{b} + {counter:r} + 32], xmm2",
+            "    movntpd [{b} + {counter:r} + 48], xmm3",
+            "    movntpd [{b} + {counter:r} + 64], xmm4",
+            "    movntpd [{b} + {counter:r} + 80], xmm5",
+            "    movntpd [{b} + {counter:r} + 96], xmm6",
+            "    movntpd [{b} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_scale_256_nt_pl(size: usize, _a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd ymm0, {q}, [{c} + {counter:r}]",
+            "    vmulpd ymm1, {q}, [{c} + {counter:r} + 32]",
+            "    vmulpd ymm2, {q}, [{c} + {counter:r} + 64]",
+            "    vmulpd ymm3, {q}, [{c} + {counter:r} + 96]",
+            "    vmulpd ymm4, {q}, [{c} + {counter:r} + 128]",
+            "    vmulpd ymm5, {q}, [{c} + {counter:r} + 160]",
+            "    vmulpd ymm6, {q}, [{c} + {counter:r} + 192]",
+            "    vmulpd ymm7, {q}, [{c} + {counter:r} + 224]",
+            "    vmovntpd [{b} + {counter:r}], ymm0",
+            "    vmovntpd [{b} + {counter:r} + 32], ymm1",
+            "    vmovntpd [{b} + {counter:r} + 64], ymm2",
+            "    vmovntpd [{b} + {counter:r} + 96], ymm3",
+            "    vmovntpd [{b} + {counter:r} + 128], ymm4",
+            "    vmovntpd [{b} + {counter:r} + 160], ymm5",
+            "    vmovntpd [{b} + {counter:r} + 192], ymm6",
+            "    vmovntpd [{b} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_64(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{a} + {counter:r} * 8]",
+            "    addsd {temp}, [{b} + {counter:r} * 8]",
+            "    movsd [{c} + {counter:r} * 8], {temp}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_128(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{a} + {counter:r}]",
+            "    addpd {temp}, [{b} + {counter:r}]",
+            "    movapd [{c} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_add_256(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd {temp}, [{a} + {counter:r}]",
+            "    vaddpd {temp}, {temp}, [{b} + {counter:r}]",
+            "    vmovapd [{c} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_64_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{a} + {counter:r}]",
+            "    addsd xmm0, [{b} + {counter:r}]",
+            "    movsd xmm1, [{a} + {counter:r} + 8]",
+            "    addsd xmm1, [{b} + {counter:r} + 8]",
+            "    movsd xmm2, [{a} + {counter:r} + 16]",
+            "    addsd xmm2, [{b} + {counter:r} + 16]",
+            "    movsd xmm3, [{a} + {counter:r} + 24]",
+            "    addsd xmm3, [{b} + {counter:r} + 24]",
+            "    movsd xmm4, [{a} + {counter:r} + 32]",
+            "    addsd xmm4, [{b} + {counter:r} + 32]",
+            "    movsd xmm5, [{a} + {counter:r} + 40]",
+            "    addsd xmm5, [{b} + {counter:r} + 40]",
+            "    movsd xmm6, [{a} + {counter:r} + 48]",
+            "    addsd xmm6, [{b} + {counter:r} + 48]",
+            "    movsd xmm7, [{a} + {counter:r} + 56]",
+            "    addsd xmm7, [{b} + {counter:r} + 56]",
+            "    movsd [{c} + {counter:r}], xmm0",
+            "    movsd [{c} + {counter:r} + 8], xmm1",
+            "    movsd [{c} + {counter:r} + 16], xmm2",
+            "    movsd [{c} + {counter:r} + 24], xmm3",
+            "    movsd [{c} + {counter:r} + 32], xmm4",
+            "    movsd [{c} + {counter:r} + 40], xmm5",
+            "    movsd [{c} + {counter:r} + 48], xmm6",
+            "    movsd [{c} + {counter:r} + 56], xmm7",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_128_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{a} + {counter:r}]",
+            "    addpd xmm0, [{b} + {counter:r}]",
+            "    movapd xmm1, [{a} + {counter:r} + 16]",
+            "    addpd xmm1, [{b} + {counter:r} + 16]",
+            "    movapd xmm2, [{a} + {counter:r} + 32]",
+            "    addpd xmm2, [{b} + {counter:r} + 32]",
+            "    movapd xmm3, [{a} + {counter:r} + 48]",
+            "    addpd xmm3, [{b} + {counter:r} + 48]",
+            "    movapd xmm4, [{a} + {counter:r} + 64]",
+            "    addpd xmm4, [{b} + {counter:r} + 64]",
+            "    movapd xmm5, [{a} + {counter:r} + 80]",
+            "    addpd xmm5, [{b} + {counter:r} + 80]",
+            "    movapd xmm6, [{a} + {counter:r} + 96]",
+            "    addpd xmm6, [{b} + {counter:r} + 96]",
+            "    movapd xmm7, [{a} + {counter:r} + 112]",
+            "    addpd xmm7, [{b} + {counter:r} + 112]",
+            "    movapd [{c} + {counter:r}], xmm0",
+            "    movapd [{c} + {counter:r} + 16], xmm1",
+            "    movapd [{c} + {counter:r} + 32], xmm2",
+            "    movapd [{c} + {counter:r} + 48], xmm3",
+            "    movapd [{c} + {counter:r} + 64], xmm4",
+            "    movapd [{c} + {counter:r} + 80], xmm5",
+            "    movapd [{c} + {counter:r} + 96], xmm6",
+            "    movapd [{c} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_add_256_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd ymm0, [{a} + {counter:r}]",
+            "    vaddpd ymm0, ymm0, [{b} + {counter:r}]",
+            "    vmovapd ymm1, [{a} + {counter:r} + 32]",
+            "    vaddpd ymm1, ymm1, [{b} + {counter:r} + 32]",
+            "    vmovapd ymm2, [{a} + {counter:r} + 64]",
+            "    vaddpd ymm2, ymm2, [{b} + {counter:r} + 64]",
+            "    vmovapd ymm3, [{a} + {counter:r} + 96]",
+            "    vaddpd ymm3, ymm3, [{b} + {counter:r} + 96]",
+            "    vmovapd ymm4, [{a} + {counter:r} + 128]",
+            "    vaddpd ymm4, ymm4, [{b} + {counter:r} + 128]",
+            "    vmovapd ymm5, [{a} + {counter:r} + 160]",
+            "    vaddpd ymm5, ymm5, [{b} + {counter:r} + 160]",
+            "    vmovapd ymm6, [{a} + {counter:r} + 192]",
+            "    vaddpd ymm6, ymm6, [{b} + {counter:r} + 192]",
+            "    vmovapd ymm7, [{a} + {counter:r} + 224]",
+            "    vaddpd ymm7, ymm7, [{b} + {counter:r} + 224]",
+            "    vmovapd [{c} + {counter:r}], ymm0",
+            "    vmovapd [{c} + {counter:r} + 32], ymm1",
+            "    vmovapd [{c} + {counter:r} + 64], ymm2",
+            "    vmovapd [{c} + {counter:r} + 96], ymm3",
+            "    vmovapd [{c} + {counter:r} + 128], ymm4",
+            "    vmovapd [{c} + {counter:r} + 160], ymm5",
+            "    vmovapd [{c} + {counter:r} + 192], ymm6",
+            "    vmovapd [{c} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_64_nt(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{a} + {counter:r} * 8]",
+            "    addsd {temp}, [{b} + {counter:r} * 8]",
+            "    movq {bits:r}, {temp}",
+            "    movnti [{c} + {counter:r} * 8], {bits:r}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            temp = out(xmm_reg) _,
+            bits = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_128_nt(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{a} + {counter:r}]",
+            "    addpd {temp}, [{b} + {counter:r}]",
+            "    movntpd [{c} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_add_256_nt(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd {temp}, [{a} + {counter:r}]",
+            "    vaddpd {temp}, {temp}, [{b} + {counter:r}]",
+            "    vmovntpd [{c} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_64_nt_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{a} + {counter:r}]",
+            "    addsd xmm0, [{b} + {counter:r}]",
+            "    movsd xmm1, [{a} + {counter:r} + 8]",
+            "    addsd xmm1, [{b} + {counter:r} + 8]",
+            "    movsd xmm2, [{a} + {counter:r} + 16]",
+            "    addsd xmm2, [{b} + {counter:r} + 16]",
+            "    movsd xmm3, [{a} + {counter:r} + 24]",
+            "    addsd xmm3, [{b} + {counter:r} + 24]",
+            "    movq {bits0:r}, xmm0",
+            "    movq {bits1:r}, xmm1",
+            "    movq {bits2:r}, xmm2",
+            "    movq {bits3:r}, xmm3",
+            "    movnti [{c} + {counter:r}], {bits0:r}",
+            "    movnti [{c} + {counter:r} + 8], {bits1:r}",
+            "    movnti [{c} + {counter:r} + 16], {bits2:r}",
+            "    movnti [{c} + {counter:r} + 24], {bits3:r}",
+            "    movsd xmm4, [{a} + {counter:r} + 32]",
+            "    addsd xmm4, [{b} + {counter:r} + 32]",
+            "    movsd xmm5, [{a} + {counter:r} + 40]",
+            "    addsd xmm5, [{b} + {counter:r} + 40]",
+            "    movsd xmm6, [{a} + {counter:r} + 48]",
+            "    addsd xmm6, [{b} + {counter:r} + 48]",
+            "    movsd xmm7, [{a} + {counter:r} + 56]",
+            "    addsd xmm7, [{b} + {counter:r} + 56]",
+            "    movq {bits0:r}, xmm4",
+            "    movq {bits1:r}, xmm5",
+            "    movq {bits2:r}, xmm6",
+            "    movq {bits3:r}, xmm7",
+            "    movnti [{c} + {counter:r} + 32], {bits0:r}",
+            "    movnti [{c} + {counter:r} + 40], {bits1:r}",
+            "    movnti [{c} + {counter:r} + 48], {bits2:r}",
+            "    movnti [{c} + {counter:r} + 56], {bits3:r}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            bits0 = out(reg) _,
+            bits1 = out(reg) _,
+            bits2 = out(reg) _,
+            bits3 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_add_128_nt_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{a} + {counter:r}]",
+            "    addpd xmm0, [{b} + {counter:r}]",
+            "    movapd xmm1, [{a} + {counter:r} + 16]",
+            "    addpd xmm1, [{b} + {counter:r} + 16]",
+            "    movapd xmm2, [{a} + {counter:r} + 32]",
+            "    addpd xmm2, [{b} + {counter:r} + 32]",
+            "    movapd xmm3, [{a} + {counter:r} + 48]",
+            "    addpd xmm3, [{b} + {counter:r} + 48]",
+            "    movapd xmm4, [{a} + {counter:r} + 64]",
+            "    addpd xmm4, [{b} + {counter:r} + 64]",
+            "    movapd xmm5, [{a} + {counter:r} + 80]",
+            "    addpd xmm5, [{b} + {counter:r} + 80]",
+            "    movapd xmm6, [{a} + {counter:r} + 96]",
+            "    addpd xmm6, [{b} + {counter:r} + 96]",
+            "    movapd xmm7, [{a} + {counter:r} + 112]",
+            "    addpd xmm7, [{b} + {counter:r} + 112]",
+            "    movntpd [{c} + {counter:r}], xmm0",
+            "    movntpd [{c} + {counter:r} + 16], xmm1",
+            "    movntpd [{c} + {counter:r} + 32], xmm2",
+            "    movntpd [{c} + {counter:r} + 48], xmm3",
+            "    movntpd [{c} + {counter:r} + 64], xmm4",
+            "    movntpd [{c} + {counter:r} + 80], xmm5",
+            "    movntpd [{c} + {counter:r} + 96], xmm6",
+            "    movntpd [{c} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_add_256_nt_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmovapd ymm0, [{a} + {counter:r}]",
+            "    vaddpd ymm0, ymm0, [{b} + {counter:r}]",
+            "    vmovapd ymm1, [{a} + {counter:r} + 32]",
+            "    vaddpd ymm1, ymm1, [{b} + {counter:r} + 32]",
+            "    vmovapd ymm2, [{a} + {counter:r} + 64]",
+            "    vaddpd ymm2, ymm2, [{b} + {counter:r} + 64]",
+            "    vmovapd ymm3, [{a} + {counter:r} + 96]",
+            "    vaddpd ymm3, ymm3, [{b} + {counter:r} + 96]",
+            "    vmovapd ymm4, [{a} + {counter:r} + 128]",
+            "    vaddpd ymm4, ymm4, [{b} + {counter:r} + 128]",
+            "    vmovapd ymm5, [{a} + {counter:r} + 160]",
+            "    vaddpd ymm5, ymm5, [{b} + {counter:r} + 160]",
+            "    vmovapd ymm6, [{a} + {counter:r} + 192]",
+            "    vaddpd ymm6, ymm6, [{b} + {counter:r} + 192]",
+            "    vmovapd ymm7, [{a} + {counter:r} + 224]",
+            "    vaddpd ymm7, ymm7, [{b} + {counter:r} + 224]",
+            "    vmovntpd [{c} + {counter:r}], ymm0",
+            "    vmovntpd [{c} + {counter:r} + 32], ymm1",
+            "    vmovntpd [{c} + {counter:r} + 64], ymm2",
+            "    vmovntpd [{c} + {counter:r} + 96], ymm3",
+            "    vmovntpd [{c} + {counter:r} + 128], ymm4",
+            "    vmovntpd [{c} + {counter:r} + 160], ymm5",
+            "    vmovntpd [{c} + {counter:r} + 192], ymm6",
+            "    vmovntpd [{c} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_64(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{c} + {counter:r} * 8]",
+            "    mulsd {temp}, {q}",
+            "    addsd {temp}, [{b} + {counter:r} * 8]",
+            "    movsd [{a} + {counter:r} * 8], {temp}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            q = in(xmm_reg) SCALAR,
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_128(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{c} + {counter:r}]",
+            "    mulpd {temp}, {q}",
+            "    addpd {temp}, [{b} + {counter:r}]",
+            "    movapd [{a} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_triad_256(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd {temp}, {q}, [{c} + {counter:r}]",
+            "    vaddpd {temp}, {temp}, [{b} + {counter:r}]",
+            "    vmovapd [{a} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_64_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{c} + {counter:r}]",
+            "    mulsd xmm0, {q}",
+            "    addsd xmm0, [{b} + {counter:r}]",
+            "    movsd xmm1, [{c} + {counter:r} + 8]",
+            "    mulsd xmm1, {q}",
+            "    addsd xmm1, [{b} + {counter:r} + 8]",
+            "    movsd xmm2, [{c} + {counter:r} + 16]",
+            "    mulsd xmm2, {q}",
+            "    addsd xmm2, [{b} + {counter:r} + 16]",
+            "    movsd xmm3, [{c} + {counter:r} + 24]",
+            "    mulsd xmm3, {q}",
+            "    addsd xmm3, [{b} + {counter:r} + 24]",
+            "    movsd xmm4, [{c} + {counter:r} + 32]",
+            "    mulsd xmm4, {q}",
+            "    addsd xmm4, [{b} + {counter:r} + 32]",
+            "    movsd xmm5, [{c} + {counter:r} + 40]",
+            "    mulsd xmm5, {q}",
+            "    addsd xmm5, [{b} + {counter:r} + 40]",
+            "    movsd xmm6, [{c} + {counter:r} + 48]",
+            "    mulsd xmm6, {q}",
+            "    addsd xmm6, [{b} + {counter:r} + 48]",
+            "    movsd xmm7, [{c} + {counter:r} + 56]",
+            "    mulsd xmm7, {q}",
+            "    addsd xmm7, [{b} + {counter:r} + 56]",
+            "    movsd [{a} + {counter:r}], xmm0",
+            "    movsd [{a} + {counter:r} + 8], xmm1",
+            "    movsd [{a} + {counter:r} + 16], xmm2",
+            "    movsd [{a} + {counter:r} + 24], xmm3",
+            "    movsd [{a} + {counter:r} + 32], xmm4",
+            "    movsd [{a} + {counter:r} + 40], xmm5",
+            "    movsd [{a} + {counter:r} + 48], xmm6",
+            "    movsd [{a} + {counter:r} + 56], xmm7",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) SCALAR,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_128_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{c} + {counter:r}]",
+            "    mulpd xmm0, {q}",
+            "    addpd xmm0, [{b} + {counter:r}]",
+            "    movapd xmm1, [{c} + {counter:r} + 16]",
+            "    mulpd xmm1, {q}",
+            "    addpd xmm1, [{b} + {counter:r} + 16]",
+            "    movapd xmm2, [{c} + {counter:r} + 32]",
+            "    mulpd xmm2, {q}",
+            "    addpd xmm2, [{b} + {counter:r} + 32]",
+            "    movapd xmm3, [{c} + {counter:r} + 48]",
+            "    mulpd xmm3, {q}",
+            "    addpd xmm3, [{b} + {counter:r} + 48]",
+            "    movapd xmm4, [{c} + {counter:r} + 64]",
+            "    mulpd xmm4, {q}",
+            "    addpd xmm4, [{b} + {counter:r} + 64]",
+            "    movapd xmm5, [{c} + {counter:r} + 80]",
+            "    mulpd xmm5, {q}",
+            "    addpd xmm5, [{b} + {counter:r} + 80]",
+            "    movapd xmm6, [{c} + {counter:r} + 96]",
+            "    mulpd xmm6, {q}",
+            "    addpd xmm6, [{b} + {counter:r} + 96]",
+            "    movapd xmm7, [{c} + {counter:r} + 112]",
+            "    mulpd xmm7, {q}",
+            "    addpd xmm7, [{b} + {counter:r} + 112]",
+            "    movapd [{a} + {counter:r}], xmm0",
+            "    movapd [{a} + {counter:r} + 16], xmm1",
+            "    movapd [{a} + {counter:r} + 32], xmm2",
+            "    movapd [{a} + {counter:r} + 48], xmm3",
+            "    movapd [{a} + {counter:r} + 64], xmm4",
+            "    movapd [{a} + {counter:r} + 80], xmm5",
+            "    movapd [{a} + {counter:r} + 96], xmm6",
+            "    movapd [{a} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_triad_256_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd ymm0, {q}, [{c} + {counter:r}]",
+            "    vaddpd ymm0, ymm0, [{b} + {counter:r}]",
+            "    vmulpd ymm1, {q}, [{c} + {counter:r} + 32]",
+            "    vaddpd ymm1, ymm1, [{b} + {counter:r} + 32]",
+            "    vmulpd ymm2, {q}, [{c} + {counter:r} + 64]",
+            "    vaddpd ymm2, ymm2, [{b} + {counter:r} + 64]",
+            "    vmulpd ymm3, {q}, [{c} + {counter:r} + 96]",
+            "    vaddpd ymm3, ymm3, [{b} + {counter:r} + 96]",
+            "    vmulpd ymm4, {q}, [{c} + {counter:r} + 128]",
+            "    vaddpd ymm4, ymm4, [{b} + {counter:r} + 128]",
+            "    vmulpd ymm5, {q}, [{c} + {counter:r} + 160]",
+            "    vaddpd ymm5, ymm5, [{b} + {counter:r} + 160]",
+            "    vmulpd ymm6, {q}, [{c} + {counter:r} + 192]",
+            "    vaddpd ymm6, ymm6, [{b} + {counter:r} + 192]",
+            "    vmulpd ymm7, {q}, [{c} + {counter:r} + 224]",
+            "    vaddpd ymm7, ymm7, [{b} + {counter:r} + 224]",
+            "    vmovapd [{a} + {counter:r}], ymm0",
+            "    vmovapd [{a} + {counter:r} + 32], ymm1",
+            "    vmovapd [{a} + {counter:r} + 64], ymm2",
+            "    vmovapd [{a} + {counter:r} + 96], ymm3",
+            "    vmovapd [{a} + {counter:r} + 128], ymm4",
+            "    vmovapd [{a} + {counter:r} + 160], ymm5",
+            "    vmovapd [{a} + {counter:r} + 192], ymm6",
+            "    vmovapd [{a} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_64_nt(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd {temp}, [{c} + {counter:r} * 8]",
+            "    mulsd {temp}, {q}",
+            "    addsd {temp}, [{b} + {counter:r} * 8]",
+            "    movq {bits:r}, {temp}",
+            "    movnti [{a} + {counter:r} * 8], {bits:r}",
+            "    inc {counter:r}",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size / 8,
+            q = in(xmm_reg) SCALAR,
+            temp = out(xmm_reg) _,
+            bits = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_128_nt(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd {temp}, [{c} + {counter:r}]",
+            "    mulpd {temp}, {q}",
+            "    addpd {temp}, [{b} + {counter:r}]",
+            "    movntpd [{a} + {counter:r}], {temp}",
+            "    add {counter:r}, 16",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            temp = out(xmm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_triad_256_nt(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd {temp}, {q}, [{c} + {counter:r}]",
+            "    vaddpd {temp}, {temp}, [{b} + {counter:r}]",
+            "    vmovntpd [{a} + {counter:r}], {temp}",
+            "    add {counter:r}, 32",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            temp = out(ymm_reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_64_nt_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movsd xmm0, [{c} + {counter:r}]",
+            "    mulsd xmm0, {q}",
+            "    addsd xmm0, [{b} + {counter:r}]",
+            "    movsd xmm1, [{c} + {counter:r} + 8]",
+            "    mulsd xmm1, {q}",
+            "    addsd xmm1, [{b} + {counter:r} + 8]",
+            "    movsd xmm2, [{c} + {counter:r} + 16]",
+            "    mulsd xmm2, {q}",
+            "    addsd xmm2, [{b} + {counter:r} + 16]",
+            "    movsd xmm3, [{c} + {counter:r} + 24]",
+            "    mulsd xmm3, {q}",
+            "    addsd xmm3, [{b} + {counter:r} + 24]",
+            "    movq {bits0:r}, xmm0",
+            "    movq {bits1:r}, xmm1",
+            "    movq {bits2:r}, xmm2",
+            "    movq {bits3:r}, xmm3",
+            "    movnti [{a} + {counter:r}], {bits0:r}",
+            "    movnti [{a} + {counter:r} + 8], {bits1:r}",
+            "    movnti [{a} + {counter:r} + 16], {bits2:r}",
+            "    movnti [{a} + {counter:r} + 24], {bits3:r}",
+            "    movsd xmm4, [{c} + {counter:r} + 32]",
+            "    mulsd xmm4, {q}",
+            "    addsd xmm4, [{b} + {counter:r} + 32]",
+            "    movsd xmm5, [{c} + {counter:r} + 40]",
+            "    mulsd xmm5, {q}",
+            "    addsd xmm5, [{b} + {counter:r} + 40]",
+            "    movsd xmm6, [{c} + {counter:r} + 48]",
+            "    mulsd xmm6, {q}",
+            "    addsd xmm6, [{b} + {counter:r} + 48]",
+            "    movsd xmm7, [{c} + {counter:r} + 56]",
+            "    mulsd xmm7, {q}",
+            "    addsd xmm7, [{b} + {counter:r} + 56]",
+            "    movq {bits0:r}, xmm4",
+            "    movq {bits1:r}, xmm5",
+            "    movq {bits2:r}, xmm6",
+            "    movq {bits3:r}, xmm7",
+            "    movnti [{a} + {counter:r} + 32], {bits0:r}",
+            "    movnti [{a} + {counter:r} + 40], {bits1:r}",
+            "    movnti [{a} + {counter:r} + 48], {bits2:r}",
+            "    movnti [{a} + {counter:r} + 56], {bits3:r}",
+            "    add {counter:r}, 64",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) SCALAR,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            bits0 = out(reg) _,
+            bits1 = out(reg) _,
+            bits2 = out(reg) _,
+            bits3 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+pub fn stream_triad_128_nt_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    movapd xmm0, [{c} + {counter:r}]",
+            "    mulpd xmm0, {q}",
+            "    addpd xmm0, [{b} + {counter:r}]",
+            "    movapd xmm1, [{c} + {counter:r} + 16]",
+            "    mulpd xmm1, {q}",
+            "    addpd xmm1, [{b} + {counter:r} + 16]",
+            "    movapd xmm2, [{c} + {counter:r} + 32]",
+            "    mulpd xmm2, {q}",
+            "    addpd xmm2, [{b} + {counter:r} + 32]",
+            "    movapd xmm3, [{c} + {counter:r} + 48]",
+            "    mulpd xmm3, {q}",
+            "    addpd xmm3, [{b} + {counter:r} + 48]",
+            "    movapd xmm4, [{c} + {counter:r} + 64]",
+            "    mulpd xmm4, {q}",
+            "    addpd xmm4, [{b} + {counter:r} + 64]",
+            "    movapd xmm5, [{c} + {counter:r} + 80]",
+            "    mulpd xmm5, {q}",
+            "    addpd xmm5, [{b} + {counter:r} + 80]",
+            "    movapd xmm6, [{c} + {counter:r} + 96]",
+            "    mulpd xmm6, {q}",
+            "    addpd xmm6, [{b} + {counter:r} + 96]",
+            "    movapd xmm7, [{c} + {counter:r} + 112]",
+            "    mulpd xmm7, {q}",
+            "    addpd xmm7, [{b} + {counter:r} + 112]",
+            "    movntpd [{a} + {counter:r}], xmm0",
+            "    movntpd [{a} + {counter:r} + 16], xmm1",
+            "    movntpd [{a} + {counter:r} + 32], xmm2",
+            "    movntpd [{a} + {counter:r} + 48], xmm3",
+            "    movntpd [{a} + {counter:r} + 64], xmm4",
+            "    movntpd [{a} + {counter:r} + 80], xmm5",
+            "    movntpd [{a} + {counter:r} + 96], xmm6",
+            "    movntpd [{a} + {counter:r} + 112], xmm7",
+            "    add {counter:r}, 128",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(xmm_reg) _mm_set1_pd(SCALAR),
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+#[cfg(target_feature = "avx")]
+pub fn stream_triad_256_nt_pl(size: usize, a: *mut u8, b: *mut u8, c: *mut u8) {
+    unsafe {
+        asm!(
+            "2:",
+            "    vmulpd ymm0, {q}, [{c} + {counter:r}]",
+            "    vaddpd ymm0, ymm0, [{b} + {counter:r}]",
+            "    vmulpd ymm1, {q}, [{c} + {counter:r} + 32]",
+            "    vaddpd ymm1, ymm1, [{b} + {counter:r} + 32]",
+            "    vmulpd ymm2, {q}, [{c} + {counter:r} + 64]",
+            "    vaddpd ymm2, ymm2, [{b} + {counter:r} + 64]",
+            "    vmulpd ymm3, {q}, [{c} + {counter:r} + 96]",
+            "    vaddpd ymm3, ymm3, [{b} + {counter:r} + 96]",
+            "    vmulpd ymm4, {q}, [{c} + {counter:r} + 128]",
+            "    vaddpd ymm4, ymm4, [{b} + {counter:r} + 128]",
+            "    vmulpd ymm5, {q}, [{c} + {counter:r} + 160]",
+            "    vaddpd ymm5, ymm5, [{b} + {counter:r} + 160]",
+            "    vmulpd ymm6, {q}, [{c} + {counter:r} + 192]",
+            "    vaddpd ymm6, ymm6, [{b} + {counter:r} + 192]",
+            "    vmulpd ymm7, {q}, [{c} + {counter:r} + 224]",
+            "    vaddpd ymm7, ymm7, [{b} + {counter:r} + 224]",
+            "    vmovntpd [{a} + {counter:r}], ymm0",
+            "    vmovntpd [{a} + {counter:r} + 32], ymm1",
+            "    vmovntpd [{a} + {counter:r} + 64], ymm2",
+            "    vmovntpd [{a} + {counter:r} + 96], ymm3",
+            "    vmovntpd [{a} + {counter:r} + 128], ymm4",
+            "    vmovntpd [{a} + {counter:r} + 160], ymm5",
+            "    vmovntpd [{a} + {counter:r} + 192], ymm6",
+            "    vmovntpd [{a} + {counter:r} + 224], ymm7",
+            "    add {counter:r}, 256",
+            "    cmp {counter:r}, {size}",
+            "    jne 2b",
+            a = in(reg) a,
+            b = in(reg) b,
+            c = in(reg) c,
+            counter = inout(reg) 0 => _,
+            size = in(reg) size,
+            q = in(ymm_reg) _mm256_set1_pd(SCALAR),
+            out("ymm0") _,
+            out("ymm1") _,
+            out("ymm2") _,
+            out("ymm3") _,
+            out("ymm4") _,
+            out("ymm5") _,
+            out("ymm6") _,
+            out("ymm7") _,
+            options(nostack),
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::dispatch;
+use memmap::{MmapMut, MmapOptions};
+use std::fs::File;
+use std::io::{self, Write};
+
+/// Mappings must start on a page.
+const PAGE: usize = 4096;
+
+/// How much of the file is mapped at once by default.
+const WINDOW: usize = 64 << 20;
+
+/// Appends to a file through a memory mapping, with non-temporal stores so that data that will not
+/// be read back does not evict anything from the caches. The file must be open for reading and
+/// writing.
+///
+/// The file is grown and the mapping moved forward one window at a time. Whatever was reserved past
+/// the data is truncated away when the writer is finished or dropped.
+pub struct NtWriter {
+    file: File,
+    window: usize,
+    map: MmapMut,
+    /// Where the mapping starts in the file.
+    offset: u64,
+    /// Where the next write goes in the mapping.
+    position: usize,
+    /// Up to where the mapping was written back by `flush`.
+    flushed: usize,
+    /// Whether windows that are no longer mapped hold data `flush` has not written back.
+    unflushed_windows: bool,
+}
+
+impl NtWriter {
+    pub fn new(file: File) -> io::Result<NtWriter> {
+        NtWriter::with_window(file, WINDOW)
+    }
+
+    /// Maps `window` bytes of the file at a time, rounded up to a page.
+    pub fn with_window(file: File, window: usize) -> io::Result<NtWriter> {
+        let window = (window.max(1) + PAGE - 1) & !(PAGE - 1);
+        let length = file.metadata()?.len();
+        let offset = length / PAGE as u64 * PAGE as u64;
+        let position = (length - offset) as usize;
+        let map = map(&file, offset, window)?;
+        Ok(NtWriter {
+            file,
+            window,
+            map,
+            offset,
+            position,
+            flushed: position,
+            unflushed_windows: false,
+        })
+    }
+
+    /// The length of the file once the reserved space is truncated.
+    pub fn len(&self) -> u64 {
+        self.offset + self.position as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Flushes the data and truncates the file to its length, reporting the errors that dropping
+    /// the writer would ignore.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.flush()?;
+        self.file.set_len(self.len())
+    }
+
+    fn advance(&mut self) -> io::Result<()> {
+        self.unflushed_windows |= self.flushed < self.position;
+        self.offset += self.window as u64;
+        self.map = map(&self.file, self.offset, self.window)?;
+        self.position = 0;
+        self.flushed = 0;
+        Ok(())
+    }
+}
+
+fn map(file: &File, offset: u64, window: usize) -> io::Result<MmapMut> {
+    file.set_len(offset + window as u64)?;
+    unsafe { MmapOptions::new().offset(offset).len(window).map_mut(file) }
+}
+
+impl Write for NtWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.position == self.window {
+            self.advance()?;
+        }
+        let length = buf.len().min(self.window - self.position);
+        let destination = self.map[self.position..].as_mut_ptr();
+        // `memcpy_nt` fences its non-temporal stores before returning.
+        dispatch::memcpy_nt(length, buf.as_ptr() as *mut u8, destination);
+        self.position += length;
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.unflushed_windows {
+            // Only the current window can be synchronised through the mapping.
+            self.file.sync_data()?;
+            self.unflushed_windows = false;
+        } else {
+            self.map
+                .flush_range(self.flushed, self.position - self.flushed)?;
+        }
+        self.flushed = self.position;
+        Ok(())
+    }
+}
+
+impl Drop for NtWriter {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(self.len());
+    }
+}
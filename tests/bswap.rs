@@ -0,0 +1,122 @@
+use memmap::MmapMut;
+use std::convert::TryInto;
+
+const SIZE: usize = 4096;
+
+fn check(
+    name: &str,
+    copy: fn(usize, *mut u8, *mut u8),
+    swap: fn(&[u8]) -> Vec<u8>,
+    element: usize,
+) {
+    let mut source = MmapMut::map_anon(SIZE).unwrap();
+    let mut destination = MmapMut::map_anon(SIZE).unwrap();
+    for (i, byte) in source.iter_mut().enumerate() {
+        *byte = (i * 7 + i / 256) as u8;
+    }
+
+    copy(SIZE, source.as_mut_ptr(), destination.as_mut_ptr());
+
+    let expected: Vec<u8> = source.chunks(element).flat_map(swap).collect();
+    assert!(
+        destination[..] == expected[..],
+        "{} does not match swap_bytes",
+        name
+    );
+}
+
+fn swap16(bytes: &[u8]) -> Vec<u8> {
+    u16::from_ne_bytes(bytes.try_into().unwrap())
+        .swap_bytes()
+        .to_ne_bytes()
+        .to_vec()
+}
+
+fn swap32(bytes: &[u8]) -> Vec<u8> {
+    u32::from_ne_bytes(bytes.try_into().unwrap())
+        .swap_bytes()
+        .to_ne_bytes()
+        .to_vec()
+}
+
+fn swap64(bytes: &[u8]) -> Vec<u8> {
+    u64::from_ne_bytes(bytes.try_into().unwrap())
+        .swap_bytes()
+        .to_ne_bytes()
+        .to_vec()
+}
+
+#[test]
+fn copy_bswap16() {
+    #[cfg(target_feature = "movbe")]
+    {
+        check("movbe", memcpy::copy_bswap16_movbe, swap16, 2);
+        check("movbe (pl)", memcpy::copy_bswap16_movbe_pl, swap16, 2);
+        check("movbe (nt)", memcpy::copy_bswap16_movbe_nt, swap16, 2);
+        check("movbe (nt+pl)", memcpy::copy_bswap16_movbe_nt_pl, swap16, 2);
+    }
+    #[cfg(target_feature = "ssse3")]
+    {
+        check("128", memcpy::copy_bswap16_128, swap16, 2);
+        check("128 (pl)", memcpy::copy_bswap16_128_pl, swap16, 2);
+        check("128 (nt)", memcpy::copy_bswap16_128_nt, swap16, 2);
+        check("128 (nt+pl)", memcpy::copy_bswap16_128_nt_pl, swap16, 2);
+    }
+    #[cfg(target_feature = "avx2")]
+    {
+        check("256", memcpy::copy_bswap16_256, swap16, 2);
+        check("256 (pl)", memcpy::copy_bswap16_256_pl, swap16, 2);
+        check("256 (nt)", memcpy::copy_bswap16_256_nt, swap16, 2);
+        check("256 (nt+pl)", memcpy::copy_bswap16_256_nt_pl, swap16, 2);
+    }
+}
+
+#[test]
+fn copy_bswap32() {
+    #[cfg(target_feature = "movbe")]
+    {
+        check("movbe", memcpy::copy_bswap32_movbe, swap32, 4);
+        check("movbe (pl)", memcpy::copy_bswap32_movbe_pl, swap32, 4);
+        check("movbe (nt)", memcpy::copy_bswap32_movbe_nt, swap32, 4);
+        check("movbe (nt+pl)", memcpy::copy_bswap32_movbe_nt_pl, swap32, 4);
+    }
+    #[cfg(target_feature = "ssse3")]
+    {
+        check("128", memcpy::copy_bswap32_128, swap32, 4);
+        check("128 (pl)", memcpy::copy_bswap32_128_pl, swap32, 4);
+        check("128 (nt)", memcpy::copy_bswap32_128_nt, swap32, 4);
+        check("128 (nt+pl)", memcpy::copy_bswap32_128_nt_pl, swap32, 4);
+    }
+    #[cfg(target_feature = "avx2")]
+    {
+        check("256", memcpy::copy_bswap32_256, swap32, 4);
+        check("256 (pl)", memcpy::copy_bswap32_256_pl, swap32, 4);
+        check("256 (nt)", memcpy::copy_bswap32_256_nt, swap32, 4);
+        check("256 (nt+pl)", memcpy::copy_bswap32_256_nt_pl, swap32, 4);
+    }
+}
+
+#[test]
+fn copy_bswap64() {
+    #[cfg(target_feature = "movbe")]
+    {
+        check("movbe", memcpy::copy_bswap64_movbe, swap64, 8);
+        check("movbe (pl)", memcpy::copy_bswap64_movbe_pl, swap64, 8);
+        check("movbe (nt)", memcpy::copy_bswap64_movbe_nt, swap64, 8);
+        check("movbe (nt+pl)", memcpy::copy_bswap64_movbe_nt_pl, swap64, 8);
+    }
+    #[cfg(target_feature = "ssse3")]
+    {
+        check("128", memcpy::copy_bswap64_128, swap64, 8);
+        check("128 (pl)", memcpy::copy_bswap64_128_pl, swap64, 8);
+        check("128 (nt)", memcpy::copy_bswap64_128_nt, swap64, 8);
+        check("128 (nt+pl)", memcpy::copy_bswap64_128_nt_pl, swap64, 8);
+    }
+    #[cfg(target_feature = "avx2")]
+    {
+        check("256", memcpy::copy_bswap64_256, swap64, 8);
+        check("256 (pl)", memcpy::copy_bswap64_256_pl, swap64, 8);
+        check("256 (nt)", memcpy::copy_bswap64_256_nt, swap64, 8);
+        check("256 (nt+pl)", memcpy::copy_bswap64_256_nt_pl, swap64, 8);
+    }
+}
@@ -0,0 +1,222 @@
+use memcpy::dispatch;
+use memcpy::registry::{self, Kernel};
+use memmap::MmapMut;
+use std::env;
+use std::ptr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The largest copy, before it is rounded up to the granularity of the kernel.
+const LARGEST: usize = 64 << 10;
+
+/// The largest offset of the source or the destination in its buffer.
+const OFFSETS: usize = 256;
+
+/// Leaves room past the copy for the bytes that must be left alone.
+const LENGTH: usize = LARGEST + 2 * OFFSETS;
+
+const ROUNDS: usize = 200;
+
+/// xorshift64*, enough to vary the cases without a dependency.
+struct Random(u64);
+
+impl Random {
+    /// Seeds from `MEMCPY_SEED` so a failure can be replayed, or from the clock.
+    fn new() -> Random {
+        let seed = env::var("MEMCPY_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64
+            })
+            | 1;
+        println!("MEMCPY_SEED={}", seed);
+        Random(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// Mostly small sizes, where the edge cases are, and a few up to `LARGEST`.
+    fn size(&mut self) -> usize {
+        if self.below(4) == 0 {
+            1 + self.below(LARGEST)
+        } else {
+            1 + self.below(1024)
+        }
+    }
+
+    /// Either a byte pattern repeated over the buffer or random bytes.
+    fn fill(&mut self, buffer: &mut [u8]) {
+        if self.below(2) == 0 {
+            let byte = self.next() as u8;
+            buffer.iter_mut().for_each(|b| *b = byte);
+        } else {
+            for chunk in buffer.chunks_mut(8) {
+                let bytes = self.next().to_ne_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+    }
+}
+
+struct Case {
+    size: usize,
+    source_offset: usize,
+    destination_offset: usize,
+}
+
+/// Copies with `copy` and with `ptr::copy_nonoverlapping`, reversing the bytes of each element of
+/// `element` bytes, and compares the whole destination buffers so that a write past either end of
+/// the copy is caught too.
+fn check(
+    name: &str,
+    copy: impl Fn(usize, *mut u8, *mut u8),
+    element: usize,
+    random: &mut Random,
+    source: &mut MmapMut,
+    destination: &mut MmapMut,
+    case: &Case,
+) {
+    random.fill(source);
+    random.fill(destination);
+    let mut expected = destination.to_vec();
+    unsafe {
+        ptr::copy_nonoverlapping(
+            source.as_ptr().add(case.source_offset),
+            expected.as_mut_ptr().add(case.destination_offset),
+            case.size,
+        );
+    }
+    let copied = case.destination_offset..case.destination_offset + case.size;
+    expected[copied]
+        .chunks_mut(element)
+        .for_each(|element| element.reverse());
+
+    copy(
+        case.size,
+        source[case.source_offset..].as_mut_ptr(),
+        destination[case.destination_offset..].as_mut_ptr(),
+    );
+
+    if let Some(i) = (0..LENGTH).find(|&i| destination[i] != expected[i]) {
+        panic!(
+            "{} differs at byte {} for size {}, source offset {} and destination offset {}",
+            name, i, case.size, case.source_offset, case.destination_offset
+        );
+    }
+}
+
+/// Checks each kernel on random cases rounded to what it accepts, `element` giving the bytes of the
+/// elements it reverses.
+fn check_kernels(kernels: &[Kernel], element: impl Fn(&Kernel) -> usize) {
+    let mut random = Random::new();
+    let mut source = MmapMut::map_anon(LENGTH).unwrap();
+    let mut destination = MmapMut::map_anon(LENGTH).unwrap();
+
+    for kernel in kernels {
+        if !kernel.is_supported() {
+            println!(
+                "skipping {}, which needs {:?}",
+                kernel.name, kernel.features
+            );
+            continue;
+        }
+
+        for _ in 0..ROUNDS {
+            // Round the case to what the kernel accepts, the buffers being page aligned.
+            let case = Case {
+                size: (random.size() + kernel.granularity - 1) & !(kernel.granularity - 1),
+                source_offset: random.below(OFFSETS) & !(kernel.alignment - 1),
+                destination_offset: random.below(OFFSETS) & !(kernel.alignment - 1),
+            };
+            if !kernel.accepts(
+                case.size,
+                source[case.source_offset..].as_ptr(),
+                destination[case.destination_offset..].as_ptr(),
+            ) {
+                continue;
+            }
+            check(
+                kernel.name,
+                kernel.copy,
+                element(kernel),
+                &mut random,
+                &mut source,
+                &mut destination,
+                &case,
+            );
+        }
+    }
+}
+
+#[test]
+fn kernels() {
+    check_kernels(registry::KERNELS, |_| 1);
+}
+
+#[test]
+fn swaps() {
+    // The bits of the elements follow `bswap` in the name.
+    check_kernels(registry::SWAPS, |kernel| {
+        kernel.name["bswap".len()..]
+            .split('_')
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap()
+            / 8
+    });
+}
+
+#[test]
+fn dispatcher() {
+    let mut random = Random::new();
+    let mut source = MmapMut::map_anon(LENGTH).unwrap();
+    let mut destination = MmapMut::map_anon(LENGTH).unwrap();
+
+    for _ in 0..ROUNDS {
+        let case = Case {
+            size: random.size() - 1,
+            source_offset: random.below(OFFSETS),
+            destination_offset: random.below(OFFSETS),
+        };
+        check(
+            "memcpy",
+            dispatch::memcpy,
+            1,
+            &mut random,
+            &mut source,
+            &mut destination,
+            &case,
+        );
+        check(
+            "memcpy_nt",
+            dispatch::memcpy_nt,
+            1,
+            &mut random,
+            &mut source,
+            &mut destination,
+            &case,
+        );
+        check(
+            "memmove",
+            dispatch::memmove,
+            1,
+            &mut random,
+            &mut source,
+            &mut destination,
+            &case,
+        );
+    }
+}
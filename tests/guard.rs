@@ -0,0 +1,184 @@
+use memcpy::{dispatch, registry};
+use std::ptr;
+
+const PAGE: usize = 4096;
+
+/// The sizes go up to two pages, so that each kernel crosses a page boundary inside the buffers.
+const LARGEST: usize = 2 * PAGE;
+
+/// Readable and writable pages between two `PROT_NONE` pages, so that any access before or after
+/// them faults.
+struct Guarded {
+    mapping: *mut u8,
+    length: usize,
+}
+
+impl Guarded {
+    fn new(length: usize) -> Guarded {
+        let length = (length + PAGE - 1) & !(PAGE - 1);
+        unsafe {
+            let mapping = libc::mmap(
+                ptr::null_mut(),
+                length + 2 * PAGE,
+                libc::PROT_NONE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+            assert_ne!(mapping, libc::MAP_FAILED);
+            let mapping = mapping as *mut u8;
+            let status = libc::mprotect(
+                mapping.add(PAGE) as *mut libc::c_void,
+                length,
+                libc::PROT_READ | libc::PROT_WRITE,
+            );
+            assert_eq!(status, 0);
+            Guarded { mapping, length }
+        }
+    }
+
+    /// The first `size` bytes, right after the lower guard page.
+    fn low(&self) -> *mut u8 {
+        self.mapping.wrapping_add(PAGE)
+    }
+
+    /// The last `size` bytes, right before the upper guard page.
+    fn high(&self, size: usize) -> *mut u8 {
+        self.mapping.wrapping_add(PAGE + self.length - size)
+    }
+}
+
+impl Drop for Guarded {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mapping as *mut libc::c_void, self.length + 2 * PAGE);
+        }
+    }
+}
+
+/// Where the child tells the parent which copy it is making, in memory they share.
+#[repr(C)]
+struct Progress {
+    size: usize,
+    high: bool,
+}
+
+/// Copies with `copy` from and to buffers flush against the guard pages, at every multiple of
+/// `granularity` from `smallest` up to `LARGEST`. A fault kills the child process it runs in rather
+/// than the test, which then fails naming the copy that faulted.
+fn check(name: &str, copy: fn(usize, *mut u8, *mut u8), smallest: usize, granularity: usize) {
+    let source = Guarded::new(LARGEST);
+    let destination = Guarded::new(LARGEST);
+    unsafe {
+        let progress = libc::mmap(
+            ptr::null_mut(),
+            PAGE,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        assert_ne!(progress, libc::MAP_FAILED);
+        let progress = progress as *mut Progress;
+
+        let child = libc::fork();
+        assert!(child >= 0, "cannot fork");
+        if child == 0 {
+            // The child of a multithreaded process must stick to async-signal-safe functions, so
+            // it neither allocates nor panics.
+            for size in (smallest..=LARGEST).step_by(granularity) {
+                for &high in &[false, true] {
+                    ptr::write_volatile(progress, Progress { size, high });
+                    if high {
+                        copy(size, source.high(size), destination.high(size));
+                    } else {
+                        copy(size, source.low(), destination.low());
+                    }
+                }
+            }
+            libc::_exit(0);
+        }
+
+        let mut status = 0;
+        assert_eq!(libc::waitpid(child, &mut status, 0), child);
+        let Progress { size, high } = ptr::read_volatile(progress);
+        libc::munmap(progress as *mut libc::c_void, PAGE);
+        if libc::WIFSIGNALED(status) {
+            panic!(
+                "{} gets signal {} copying {} bytes {} a guard page",
+                name,
+                libc::WTERMSIG(status),
+                size,
+                if high { "right before" } else { "right after" }
+            );
+        }
+        assert!(
+            libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0,
+            "{} exits with status {:#x} copying {} bytes",
+            name,
+            status,
+            size
+        );
+    }
+}
+
+/// The sizes a kernel of `granularity` must refuse: none, and one byte either side of the
+/// granularity when it is more than a byte.
+fn refused(granularity: usize) -> Vec<usize> {
+    let mut sizes = vec![0];
+    if granularity > 1 {
+        sizes.extend([granularity - 1, granularity + 1]);
+    }
+    sizes
+}
+
+#[test]
+fn kernels() {
+    let mut failures = Vec::new();
+    for kernel in registry::KERNELS.iter().chain(registry::SWAPS) {
+        if !kernel.is_supported() {
+            println!(
+                "skipping {}, which needs {:?}",
+                kernel.name, kernel.features
+            );
+            continue;
+        }
+        // The kernels only take sizes that are multiples of their granularity, which is itself a
+        // multiple of their alignment, and the guard pages are page aligned, so both placements
+        // meet the alignment. The sizes they do not take, 0 among them, are never copied, but
+        // `accepts` must refuse them.
+        let result = std::panic::catch_unwind(|| {
+            let (source, destination) = (Guarded::new(PAGE), Guarded::new(PAGE));
+            for size in refused(kernel.granularity) {
+                assert!(
+                    !kernel.accepts(size, source.low(), destination.low()),
+                    "{} accepts {} bytes",
+                    kernel.name,
+                    size
+                );
+            }
+            check(
+                kernel.name,
+                kernel.copy,
+                kernel.granularity,
+                kernel.granularity,
+            );
+        });
+        if let Err(error) = result {
+            failures.push(
+                error
+                    .downcast::<String>()
+                    .map_or_else(|_| kernel.name.to_string(), |message| *message),
+            );
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn dispatcher() {
+    // The dispatcher takes any size, 0 included.
+    check("memcpy", dispatch::memcpy, 0, 1);
+    check("memcpy_nt", dispatch::memcpy_nt, 0, 1);
+    check("memmove", dispatch::memmove, 0, 1);
+}
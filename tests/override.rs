@@ -0,0 +1,60 @@
+use criterion::black_box;
+
+#[test]
+fn symbols_resolve_to_the_overrides() {
+    assert_eq!(
+        libc::memcpy as *const (),
+        memcpy::overrides::memcpy as *const ()
+    );
+    assert_eq!(
+        libc::memmove as *const (),
+        memcpy::overrides::memmove as *const ()
+    );
+    assert_eq!(
+        libc::memset as *const (),
+        memcpy::overrides::memset as *const ()
+    );
+    assert_eq!(
+        libc::memcmp as *const (),
+        memcpy::overrides::memcmp as *const ()
+    );
+}
+
+#[test]
+fn compiler_generated_calls() {
+    let source: Vec<u8> = (0..1 << 20).map(|i| (i * 7 + i / 251) as u8).collect();
+    let mut destination = vec![0; source.len()];
+
+    destination[3..].copy_from_slice(black_box(&source[..source.len() - 3]));
+    assert!(destination[3..] == source[..source.len() - 3]);
+    assert!(destination[..3] == [0, 0, 0]);
+
+    destination.copy_within(..source.len() - 5, 5);
+    assert!(destination[8..] == source[..source.len() - 8]);
+
+    let moved = black_box([0xa5_u8; 4096]);
+    let copy = black_box(moved);
+    assert!(copy.iter().all(|&byte| byte == 0xa5));
+
+    assert!(black_box(&source[..]) != black_box(&destination[..]));
+    assert!(black_box(&source[..]) == black_box(&source.clone()[..]));
+}
+
+#[test]
+fn memcmp_orders_like_the_first_differing_byte() {
+    let left: Vec<u8> = (0..100).collect();
+    for i in 0..left.len() {
+        let mut right = left.clone();
+        right[i] = right[i].wrapping_add(0x80);
+        let expected = if left[i] < right[i] { -1 } else { 1 };
+        for size in i + 1..=left.len() {
+            let result = unsafe {
+                libc::memcmp(left.as_ptr() as *const _, right.as_ptr() as *const _, size)
+            };
+            assert_eq!(result.signum(), expected, "differing at {} of {}", i, size);
+        }
+        let result =
+            unsafe { libc::memcmp(left.as_ptr() as *const _, right.as_ptr() as *const _, i) };
+        assert_eq!(result, 0, "equal up to {}", i);
+    }
+}
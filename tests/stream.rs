@@ -0,0 +1,70 @@
+use memcpy::registry::{self, Stream};
+use memmap::MmapMut;
+
+const SIZE: usize = 4096;
+/// The `q` of STREAM, which the kernels write, scale by and add a multiple of.
+const SCALAR: f64 = 3.0;
+
+fn array(value: impl Fn(usize) -> f64) -> MmapMut {
+    let mut array = MmapMut::map_anon(SIZE).unwrap();
+    for (i, element) in array.chunks_mut(8).enumerate() {
+        element.copy_from_slice(&value(i).to_ne_bytes());
+    }
+    array
+}
+
+fn elements(array: &MmapMut) -> Vec<f64> {
+    array.chunks(8).map(read).collect()
+}
+
+fn read(element: &[u8]) -> f64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(element);
+    f64::from_ne_bytes(bytes)
+}
+
+fn check(stream: &Stream) {
+    let mut a = array(|i| i as f64 * 0.5);
+    let mut b = array(|i| 1.0 - i as f64);
+    let mut c = array(|i| (i % 7) as f64 + 0.25);
+    let (before_a, before_b, before_c) = (elements(&a), elements(&b), elements(&c));
+
+    (stream.run)(SIZE, a.as_mut_ptr(), b.as_mut_ptr(), c.as_mut_ptr());
+
+    let (mut expected_a, mut expected_b, mut expected_c) =
+        (before_a.clone(), before_b.clone(), before_c.clone());
+    let i = 0..SIZE / 8;
+    match stream.name.split('_').next().unwrap() {
+        "read" => {}
+        "write" => i.for_each(|i| expected_a[i] = SCALAR),
+        "copy" => i.for_each(|i| expected_c[i] = before_a[i]),
+        "scale" => i.for_each(|i| expected_b[i] = SCALAR * before_c[i]),
+        "add" => i.for_each(|i| expected_c[i] = before_a[i] + before_b[i]),
+        "triad" => i.for_each(|i| expected_a[i] = before_b[i] + SCALAR * before_c[i]),
+        operation => panic!("{} is not an operation of STREAM", operation),
+    }
+    assert!(
+        elements(&a) == expected_a,
+        "{} does not write a as expected",
+        stream.name
+    );
+    assert!(
+        elements(&b) == expected_b,
+        "{} does not write b as expected",
+        stream.name
+    );
+    assert!(
+        elements(&c) == expected_c,
+        "{} does not write c as expected",
+        stream.name
+    );
+}
+
+#[test]
+fn streams() {
+    for stream in registry::STREAMS {
+        if stream.is_supported() {
+            check(stream);
+        }
+    }
+}
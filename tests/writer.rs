@@ -0,0 +1,72 @@
+use memcpy::NtWriter;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process;
+
+fn path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("memcpy-writer-{}-{}", name, process::id()))
+}
+
+fn open(path: &PathBuf) -> File {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .unwrap()
+}
+
+fn pattern(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i * 7 + i / 251) as u8).collect()
+}
+
+#[test]
+fn writes_across_windows() {
+    let path = path("windows");
+    let data = pattern(1 << 20);
+
+    let mut writer = NtWriter::with_window(open(&path), 64 << 10).unwrap();
+    let mut written = 0;
+    for (i, length) in [1, 31, 4096, 100_000, 3, 65_536, 300_000]
+        .iter()
+        .cycle()
+        .enumerate()
+    {
+        let length = (*length).min(data.len() - written);
+        writer.write_all(&data[written..written + length]).unwrap();
+        written += length;
+        if i % 3 == 0 {
+            writer.flush().unwrap();
+        }
+        if written == data.len() {
+            break;
+        }
+    }
+    assert_eq!(writer.len(), data.len() as u64);
+    writer.finish().unwrap();
+
+    let contents = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(contents == data);
+}
+
+#[test]
+fn appends_to_existing_data() {
+    let path = path("append");
+    let data = pattern(50_000);
+    fs::write(&path, &data[..5_000]).unwrap();
+
+    {
+        let mut writer = NtWriter::with_window(open(&path), 4096).unwrap();
+        assert_eq!(writer.len(), 5_000);
+        writer.write_all(&data[5_000..]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let contents = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(contents == data);
+}